@@ -1,4 +1,5 @@
 use std::{
+    convert::TryFrom,
     sync::{
         atomic::{AtomicBool, Ordering},
         Mutex,
@@ -16,6 +17,7 @@ use worker::*;
 mod alarm;
 mod counter;
 mod r2;
+mod service;
 mod test;
 mod utils;
 
@@ -50,6 +52,11 @@ struct FileSize {
     size: u32,
 }
 
+#[derive(Deserialize, Serialize)]
+struct Visits {
+    count: u32,
+}
+
 pub struct SomeSharedData {
     regex: regex::Regex,
 }
@@ -88,7 +95,11 @@ pub fn start() {
 }
 
 #[event(fetch)]
-pub async fn main(req: Request, env: Env, _ctx: worker::Context) -> Result<Response> {
+pub async fn main(req: Request, env: Env, ctx: worker::Context) -> Result<Response> {
+    if req.path().starts_with("/tower/") {
+        return worker::tower::serve(service::EnvService, req, env, ctx).await;
+    }
+
     let data = SomeSharedData {
         regex: regex::Regex::new(r"^\d{4}-\d{2}-\d{2}$").unwrap(),
     };
@@ -194,6 +205,181 @@ pub async fn main(req: Request, env: Env, _ctx: worker::Context) -> Result<Respo
             Response::ok("returned your headers to you.")
                 .map(|res| res.with_headers(headers.into()))
         })
+        .post_async("/http-roundtrip", |req, _ctx| async move {
+            let req = http::Request::<HttpBody>::try_from(req)?;
+            let reversed: String = req.into_body().text().await?.chars().rev().collect();
+
+            let res = http::Response::builder()
+                .status(201)
+                .header("x-reversed", "true")
+                .body(reversed)
+                .unwrap();
+            Response::try_from(res)
+        })
+        .get_async("/http-request-conversion", |req, _ctx| async move {
+            let url = req.url()?;
+            let http_req = http::Request::get(url.as_str())
+                .header("x-converted", "true")
+                .body(String::new())
+                .unwrap();
+            let req = Request::try_from(http_req)?;
+
+            Response::ok(format!(
+                "{} {} {}",
+                req.method().as_ref(),
+                req.headers().get("x-converted")?.unwrap_or_default(),
+                req.inner().body().is_some()
+            ))
+        })
+        .get("/response-builder", |_req, _ctx| {
+            Response::builder()
+                .status(201)
+                .header("x-custom", "one")
+                .header("x-custom", "two")
+                .cache_control("no-store")
+                .json(&serde_json::json!({ "built": true }))
+        })
+        .get("/into-response/:status", |_req, ctx| {
+            let status: u16 = match ctx.param("status").unwrap().parse() {
+                Ok(status) => status,
+                Err(_) => return (400, "invalid status").into_response(),
+            };
+            (status, format!("status {status}")).into_response()
+        })
+        .get("/cookies", |req, _ctx| {
+            let visits: u32 = req
+                .cookie("visits")?
+                .and_then(|visits| visits.parse().ok())
+                .unwrap_or_default();
+
+            Response::ok(format!("visits: {visits}"))?
+                .with_cookie(&Cookie::new("visits", (visits + 1).to_string())?.with_path("/")?)?
+                .with_cookie(
+                    &Cookie::new("session", "abc")?
+                        .with_max_age(60)
+                        .with_same_site(SameSite::Strict)
+                        .with_secure(true)
+                        .with_http_only(true),
+                )
+        })
+        .get("/set-cookies", |_req, _ctx| {
+            let response = Response::ok("")?
+                .with_cookie(&Cookie::new("first", "1")?)?
+                .with_cookie(&Cookie::new("second", "2")?.with_path("/")?)?;
+            let rejected = Cookie::new("third", "3; Domain=evil").is_err();
+
+            Response::from_json(&serde_json::json!({
+                "setCookies": response.set_cookies(),
+                "injectionRejected": rejected,
+            }))
+        })
+        .get_async("/session", |req, ctx| async move {
+            // The cookie name can be overridden to check that sessions can't be replayed under
+            // another name.
+            let name = req
+                .url()?
+                .query_pairs()
+                .find(|(key, _)| key == "name")
+                .map(|(_, name)| name.into_owned())
+                .unwrap_or_else(|| "session".into());
+            let session = Session::from_secrets(&ctx.env, name, &["SOME_VARIABLE"])?
+                .with_encryption(true)
+                .with_max_age(60);
+
+            let visits = session.load::<Visits>(&req).await?;
+            let visits = Visits {
+                count: visits.map(|visits| visits.count).unwrap_or_default() + 1,
+            };
+
+            Response::ok(format!("visits: {}", visits.count))?
+                .with_cookie(&session.cookie(&visits).await?)
+        })
+        .get("/sse", |_req, _ctx| {
+            let events = futures_util::stream::iter(1..=3).then(|progress| async move {
+                Delay::from(Duration::from_millis(50)).await;
+                sse::Event::json(&serde_json::json!({ "progress": progress }))
+                    .map(|event| event.with_event("progress").with_id(progress.to_string()))
+            });
+            let done = futures_util::stream::once(async { Ok(sse::Event::data("done\nbye")) });
+
+            Response::from_sse(
+                sse::SseStream::new(events.chain(done)).with_keep_alive(Duration::from_millis(20)),
+            )
+        })
+        .post_async("/body-limit", |mut req, _ctx| async move {
+            let text = req.text_limited(16).await?;
+            Response::ok(text)
+        })
+        .post_async("/body-limit-clone", |mut req, _ctx| async move {
+            // Clones keep the limit of the request they were cloned from.
+            req.set_body_limit(16);
+            let text = req.clone()?.text().await?;
+            Response::ok(text)
+        })
+        .get_async("/request-init-body", |_req, _ctx| async move {
+            async fn sent(init: &RequestInit) -> Result<String> {
+                let mut req = Request::new_with_init("https://example.com", init)?;
+                let content_type = req.headers().get("content-type")?.unwrap_or_default();
+                Ok(format!("{content_type} {}", req.text().await?))
+            }
+
+            let mut json = RequestInit::new();
+            json.with_method(Method::Post)
+                .with_json(&serde_json::json!({ "a": 1 }))?;
+            let mut text = RequestInit::new();
+            text.with_method(Method::Post).with_text("text")?;
+            let mut form = RequestInit::new();
+            form.with_method(Method::Post)
+                .with_url_encoded(&[("a", "1"), ("b", "2")])?;
+            let mut stream = RequestInit::new();
+            stream
+                .with_method(Method::Post)
+                .with_stream(futures_util::stream::iter(["a", "b"]).map(Ok::<_, Error>))?;
+
+            Response::from_json(&vec![
+                sent(&json).await?,
+                sent(&text).await?,
+                sent(&form).await?,
+                sent(&stream).await?,
+            ])
+        })
+        .get_async("/request-init-abort", |_req, _ctx| async move {
+            let signal = AbortSignal::abort();
+            let mut init = RequestInit::new();
+            init.with_signal(Some(&signal));
+
+            let req = Request::new_with_init("https://example.com", &init)?;
+            match Fetch::Request(req).send().await {
+                Ok(_) => Response::error("request was not aborted", 500),
+                Err(_) => Response::ok("aborted"),
+            }
+        })
+        .get_async("/request-init-options", |_req, _ctx| async move {
+            let mut no_store = RequestInit::new();
+            no_store.with_cache(RequestCache::NoStore);
+            let req = Request::new_with_init("https://example.com", &no_store)?;
+            let cache = js_sys::Reflect::get(req.inner(), &"cache".into())?;
+
+            let mut integrity = RequestInit::new();
+            integrity.with_integrity("sha256-AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=");
+            let integrity_rejected = match Request::new_with_init("https://example.com", &integrity)
+            {
+                Ok(req) => match Fetch::Request(req).send().await {
+                    Ok(mut res) => res.bytes().await.is_err(),
+                    Err(_) => true,
+                },
+                Err(_) => true,
+            };
+
+            Response::from_json(&serde_json::json!({
+                "cache": cache.as_string(),
+                "integrityRejected": integrity_rejected,
+            }))
+        })
+        .on_method("PURGE".into(), "/purge/:key", |req, ctx| {
+            let key = ctx.param("key").unwrap();
+            Response::ok(format!("{} {key}", req.method().as_ref()))
+        })
         .post_async("/formdata-name", |mut req, _ctx| async move {
             let form = req.form_data().await?;
             const NAME: &str = "name";
@@ -688,7 +874,26 @@ pub async fn main(req: Request, env: Env, _ctx: worker::Context) -> Result<Respo
                     Response::error(format!("Failed to send message to queue: {err:?}"), 500)
                 }
             }
-        }).get_async("/queue", |_req, _ctx| async move {
+        })
+        .post_async("/queue/send-batch", |mut req, ctx| async move {
+            let ids: Vec<Uuid> = req.json().await?;
+            let my_queue = ctx.env.queue("my_queue")?;
+            let messages = ids.into_iter().map(|id| QueueBody {
+                id,
+                id_string: id.to_string(),
+            });
+            let options = SendOptions {
+                content_type: Some(QueueContentType::Json),
+                delay_seconds: None,
+            };
+            match my_queue.send_batch_with_options(messages, &options).await {
+                Ok(_) => Response::ok("Messages sent"),
+                Err(err) => {
+                    Response::error(format!("Failed to send messages to queue: {err:?}"), 500)
+                }
+            }
+        })
+        .get_async("/queue", |_req, _ctx| async move {
             let guard = GLOBAL_QUEUE_STATE.lock().unwrap();
             let messages: Vec<QueueBody> = guard.clone();
             Response::from_json(&messages)
@@ -697,10 +902,20 @@ pub async fn main(req: Request, env: Env, _ctx: worker::Context) -> Result<Respo
         .get_async("/r2/list", r2::list)
         .get_async("/r2/get-empty", r2::get_empty)
         .get_async("/r2/get", r2::get)
+        .get_async("/r2/object/:key", r2::object)
+        .on_async("/r2/serve/:key", r2::serve)
         .put_async("/r2/put", r2::put)
         .put_async("/r2/put-properties", r2::put_properties)
         .put_async("/r2/put-multipart", r2::put_multipart)
+        .put_async("/r2/put-multipart-writer", r2::put_multipart_writer)
+        .put_async(
+            "/r2/put-multipart-writer-copy",
+            r2::put_multipart_writer_copy,
+        )
+        .on_async("/r2/uploads/*key", r2::uploads)
+        .put_async("/r2/json", r2::json)
         .delete_async("/r2/delete", r2::delete)
+        .delete_async("/r2/delete-many", r2::delete_many)
         .or_else_any_method_async("/*catchall", |_, ctx| async move {
             console_log!(
                 "[or_else_any_method_async] caught: {}",
@@ -740,15 +955,22 @@ pub struct QueueBody {
 
 #[event(queue)]
 pub async fn queue(message_batch: MessageBatch<QueueBody>, _env: Env, _ctx: Context) -> Result<()> {
+    consume_queue_messages(message_batch)
+}
+
+/// Records every message of the batch in the global queue state, acknowledging each of them.
+pub fn consume_queue_messages(message_batch: MessageBatch<QueueBody>) -> Result<()> {
     let mut guard = GLOBAL_QUEUE_STATE.lock().unwrap();
     for message in message_batch.messages()? {
         console_log!(
-            "Received queue message {:?}, with id {} and timestamp: {}",
+            "Received queue message {:?}, with id {}, timestamp: {} and attempts: {}",
             message.body,
             message.id,
-            message.timestamp.to_string()
+            message.timestamp.to_string(),
+            message.attempts
         );
-        guard.push(message.body);
+        guard.push(message.body.clone());
+        message.ack();
     }
     Ok(())
 }
@@ -1,9 +1,10 @@
-use std::{collections::HashMap, sync::Mutex};
+use std::{collections::HashMap, convert::TryFrom, sync::Mutex};
 
-use futures_util::StreamExt;
+use futures_util::{AsyncReadExt, StreamExt};
 use worker::{
-    Bucket, Conditional, Data, Date, FixedLengthStream, HttpMetadata, Include, Request, Response,
-    Result, RouteContext,
+    Bucket, Conditional, Data, Date, FixedLengthStream, HttpMetadata, Include,
+    MultipartUploadHandler, R2Writer, Request, Response, Result, RouteContext, StorageClass,
+    Versioned,
 };
 
 use crate::SomeSharedData;
@@ -61,6 +62,27 @@ pub async fn list(_req: Request, ctx: RouteContext<SomeSharedData>) -> Result<Re
     assert_eq!(objects_2.objects().len(), 2);
     assert!(!objects_2.truncated());
 
+    let streamed = bucket
+        .list()
+        .limit(1)
+        .stream()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(streamed.len(), 3);
+
+    let prefixes = bucket
+        .list()
+        .limit(1)
+        .delimiter("-")
+        .stream_delimited_prefixes()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(prefixes, vec!["no-", "with-"]);
+
     let with_prefix = bucket.list().prefix("no-").execute().await?;
     assert_eq!(with_prefix.objects().len(), 2);
     assert!(!with_prefix.truncated());
@@ -127,6 +149,15 @@ pub async fn get(_req: Request, ctx: RouteContext<SomeSharedData>) -> Result<Res
 
     assert_eq!(item_body.text().await?, "text");
 
+    let item = bucket.get("no-props").execute().await?.unwrap();
+    let mut reader = item.body().unwrap().into_async_read()?;
+    let mut text = String::new();
+    reader
+        .read_to_string(&mut text)
+        .await
+        .map_err(|e| e.to_string())?;
+    assert_eq!(text, "text");
+
     let (http_metadata, custom_metadata) = dummy_properties();
     let item = bucket.get("with-props").execute().await?.unwrap();
     let item_body = item.body().unwrap();
@@ -139,6 +170,25 @@ pub async fn get(_req: Request, ctx: RouteContext<SomeSharedData>) -> Result<Res
     Response::ok("ok")
 }
 
+pub async fn object(_req: Request, ctx: RouteContext<SomeSharedData>) -> Result<Response> {
+    let bucket = ctx.bucket("SEEDED_BUCKET")?;
+    seed_bucket(&bucket).await?;
+
+    let key = ctx.param("key").unwrap();
+    match bucket.get(key).execute().await? {
+        Some(object) => Response::try_from(object),
+        None => Response::error("Not Found", 404),
+    }
+}
+
+pub async fn serve(req: Request, ctx: RouteContext<SomeSharedData>) -> Result<Response> {
+    let bucket = ctx.bucket("SEEDED_BUCKET")?;
+    seed_bucket(&bucket).await?;
+
+    let key = ctx.param("key").unwrap();
+    bucket.serve(&req, key).await
+}
+
 pub async fn put(_req: Request, ctx: RouteContext<SomeSharedData>) -> Result<Response> {
     let bucket = ctx.bucket("PUT_BUCKET")?;
 
@@ -146,11 +196,27 @@ pub async fn put(_req: Request, ctx: RouteContext<SomeSharedData>) -> Result<Res
     let stream = futures_util::stream::repeat_with(|| Ok(vec![0u8; 16])).take(16);
     let fixed_stream = FixedLengthStream::wrap(stream, 16 * 16);
 
-    bucket.put("text", "text".to_string()).execute().await?;
+    let text_obj = bucket
+        .put("text", "text".to_string())
+        .storage_class(StorageClass::Standard)
+        .execute()
+        .await?;
+    assert_eq!(text_obj.storage_class()?, StorageClass::Standard);
     bucket.put("bytes", vec![0u8; 32]).execute().await?;
     bucket.put("empty", Data::Empty).execute().await?;
     bucket.put("stream", fixed_stream).execute().await?;
 
+    // Conditional puts should not overwrite the object if the condition fails.
+    let overwritten = bucket
+        .put("text", "overwritten".to_string())
+        .only_if(Conditional {
+            etag_does_not_match: Some(text_obj.etag()),
+            ..Default::default()
+        })
+        .execute_conditional()
+        .await?;
+    assert!(overwritten.is_none());
+
     // Now let's get the objects again manually and make sure everything is in-tact.
 
     // Internally `.text()` calls `.bytes()` which calls `.stream()`, so most cases are covered
@@ -236,6 +302,84 @@ pub async fn put_multipart(_req: Request, ctx: RouteContext<SomeSharedData>) ->
     Response::ok("ok")
 }
 
+pub async fn put_multipart_writer(
+    mut req: Request,
+    ctx: RouteContext<SomeSharedData>,
+) -> Result<Response> {
+    let bucket = ctx.bucket("PUT_BUCKET")?;
+
+    let upload = bucket
+        .create_multipart_upload("multipart_writer")
+        .execute()
+        .await?;
+    let object = R2Writer::new(upload)
+        .concurrency(2)
+        .upload(req.stream()?)
+        .await?;
+
+    Response::ok(object.size().to_string())
+}
+
+pub async fn put_multipart_writer_copy(
+    mut req: Request,
+    ctx: RouteContext<SomeSharedData>,
+) -> Result<Response> {
+    let bucket = ctx.bucket("PUT_BUCKET")?;
+    let body = req.bytes().await?;
+
+    let upload = bucket
+        .create_multipart_upload("multipart_writer_copy")
+        .execute()
+        .await?;
+    let mut writer = R2Writer::new(upload);
+    futures_util::io::copy(futures_util::io::Cursor::new(body), &mut writer)
+        .await
+        .map_err(|e| e.to_string())?;
+    let object = writer.finish().await?;
+
+    Response::ok(object.size().to_string())
+}
+
+pub async fn uploads(req: Request, ctx: RouteContext<SomeSharedData>) -> Result<Response> {
+    let bucket = ctx.bucket("PUT_BUCKET")?;
+
+    let key = ctx.param("key").unwrap();
+    MultipartUploadHandler::new(&bucket).handle(req, key).await
+}
+
+pub async fn json(_req: Request, ctx: RouteContext<SomeSharedData>) -> Result<Response> {
+    let bucket = ctx.bucket("PUT_BUCKET")?;
+
+    let counter = bucket.put_json("counter.json", &0u32)?.execute().await?;
+    assert_eq!(
+        counter.http_metadata().content_type.as_deref(),
+        Some("application/json")
+    );
+
+    let Versioned { value, etag } = bucket.get_json::<u32>("counter.json").await?.unwrap();
+    assert_eq!(value, 0);
+
+    let swap = |etag: String| {
+        bucket
+            .put_json("counter.json", &(value + 1))
+            .map(|builder| {
+                builder.only_if(Conditional {
+                    etag_matches: Some(etag),
+                    ..Default::default()
+                })
+            })
+    };
+
+    // The first swap succeeds, but the second fails as the object changed since it was read.
+    assert!(swap(etag.clone())?.execute_conditional().await?.is_some());
+    assert!(swap(etag)?.execute().await.is_err());
+
+    let counter = bucket.get_json::<u32>("counter.json").await?.unwrap();
+    assert_eq!(counter.value, 1);
+
+    Response::ok("ok")
+}
+
 pub async fn delete(_req: Request, ctx: RouteContext<SomeSharedData>) -> Result<Response> {
     let bucket = ctx.bucket("DELETE_BUCKET")?;
 
@@ -252,6 +396,25 @@ pub async fn delete(_req: Request, ctx: RouteContext<SomeSharedData>) -> Result<
     Response::ok("ok")
 }
 
+pub async fn delete_many(_req: Request, ctx: RouteContext<SomeSharedData>) -> Result<Response> {
+    let bucket = ctx.bucket("DELETE_MANY_BUCKET")?;
+
+    let keys: Vec<String> = (0..5).map(|i| format!("key-{i}")).collect();
+    for key in &keys {
+        bucket.put(key, Data::Empty).execute().await?;
+    }
+
+    let objects = bucket.list().execute().await?;
+    assert_eq!(objects.objects().len(), keys.len());
+
+    bucket.delete_many(keys).await?;
+
+    let objects = bucket.list().execute().await?;
+    assert_eq!(objects.objects().len(), 0);
+
+    Response::ok("ok")
+}
+
 async fn put_full_properties(
     name: &str,
     bucket: &Bucket,
@@ -0,0 +1,39 @@
+use std::{
+    future::{ready, Ready},
+    task::{Context, Poll},
+};
+
+use tower_service::Service;
+use worker::{tower::WorkerEnv, Error, HttpBody, Result};
+
+/// A service responding with the path of the request and a variable read from its [WorkerEnv],
+/// served with `worker::tower::serve`.
+pub struct EnvService;
+
+impl Service<http::Request<HttpBody>> for EnvService {
+    type Response = http::Response<String>;
+    type Error = Error;
+    type Future = Ready<Result<Self::Response>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<HttpBody>) -> Self::Future {
+        ready(respond(&req))
+    }
+}
+
+fn respond(req: &http::Request<HttpBody>) -> Result<http::Response<String>> {
+    let env = req
+        .extensions()
+        .get::<WorkerEnv>()
+        .ok_or_else(|| Error::RustError("missing WorkerEnv extension".into()))?;
+    let variable = env.var("SOME_VARIABLE")?.to_string();
+
+    Ok(http::Response::new(format!(
+        "{} {}",
+        req.uri().path(),
+        variable
+    )))
+}
@@ -46,3 +46,28 @@ fn receive_message_from_queue() {
     assert_eq!(message.id, id);
     assert_eq!(message.id_string, id.to_string());
 }
+
+#[test]
+fn send_batch_to_queue() {
+    // Arrange
+    expect_wrangler();
+    let ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+
+    // Act
+    let response = post("queue/send-batch", |r| r.json(&ids));
+    assert!(response.status().is_success());
+
+    // Assert
+    retry::retry(Fixed::from_millis(500).take(5), || {
+        let messages: Vec<QueueBody> = util::get("queue", |r| r)
+            .json()
+            .expect("Failed to get Json");
+
+        if ids.iter().all(|id| messages.iter().any(|m| m.id == *id)) {
+            Ok(())
+        } else {
+            Err("Failed to find every expected message")
+        }
+    })
+    .unwrap();
+}
@@ -0,0 +1,179 @@
+// These tests exercise the queue consumer against in-memory test doubles rather than a running
+// wrangler, and so must be run with `wasm-pack test --node`.
+#![cfg(target_arch = "wasm32")]
+
+use uuid::Uuid;
+use wasm_bindgen_test::wasm_bindgen_test;
+use worker::{
+    js_sys::Object,
+    testing::{MemoryQueue, MessageBatchBuilder, MessageOutcome},
+    wasm_bindgen::JsCast,
+    worker_sys, Context, Date, Env, Error, MessageBatch, QueueContext, QueueRouter, Result,
+    TypedMessageBatch, TypedQueue,
+};
+use worker_sandbox::{consume_queue_messages, QueueBody};
+
+#[wasm_bindgen_test]
+async fn sent_messages_are_consumed_and_acked() {
+    // Arrange
+    let memory = MemoryQueue::new();
+    let id = Uuid::new_v4();
+    memory
+        .queue()
+        .send(&QueueBody {
+            id,
+            id_string: id.to_string(),
+        })
+        .await
+        .unwrap();
+
+    // Act
+    let (batch, outcome) = memory.drain_batch("my_queue").build();
+    consume_queue_messages(MessageBatch::from(batch)).unwrap();
+
+    // Assert
+    assert!(memory.is_empty());
+    assert_eq!(outcome.outcome("0"), Some(MessageOutcome::Acked));
+}
+
+#[wasm_bindgen_test]
+fn malformed_message_fails_the_batch() {
+    // Arrange
+    let id = Uuid::new_v4();
+    let (batch, outcome) = MessageBatchBuilder::new("my_queue")
+        .message(&QueueBody {
+            id,
+            id_string: id.to_string(),
+        })
+        .unwrap()
+        .message_with("malformed", Date::now(), 3, &"not a queue body")
+        .unwrap()
+        .build();
+
+    // Act
+    let result = consume_queue_messages(MessageBatch::from(batch));
+
+    // Assert
+    assert!(result.is_err());
+    assert_eq!(outcome.unhandled(), vec!["0", "malformed"]);
+}
+
+#[wasm_bindgen_test]
+async fn typed_messages_keep_malformed_bodies_handleable() {
+    // Arrange
+    let memory = MemoryQueue::new();
+    let id = Uuid::new_v4();
+    TypedQueue::from(memory.queue())
+        .send(&QueueBody {
+            id,
+            id_string: id.to_string(),
+        })
+        .await
+        .unwrap();
+    let (batch, outcome) = memory
+        .drain_batch("my_queue")
+        .message_with("malformed", Date::now(), 1, &"not a queue body")
+        .unwrap()
+        .build();
+
+    // Act
+    let batch: TypedMessageBatch<QueueBody> = TypedMessageBatch::from(batch);
+    let messages = batch.messages();
+    for message in &messages {
+        match message.body() {
+            Ok(_) => message.ack(),
+            Err(_) => message.retry(),
+        }
+    }
+
+    // Assert
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].body().unwrap().id, id);
+    assert!(messages[1].body().is_err());
+    assert_eq!(outcome.acked(), vec!["0"]);
+    assert_eq!(outcome.retried(), vec!["malformed"]);
+}
+
+fn orders(batch: MessageBatch<QueueBody>, _ctx: QueueContext<()>) -> Result<()> {
+    if batch.queue() != "orders" {
+        return Err(Error::RustError(format!(
+            "orders handler got {}",
+            batch.queue()
+        )));
+    }
+    for message in batch.messages()? {
+        message.ack();
+    }
+    Ok(())
+}
+
+async fn emails(batch: TypedMessageBatch<String>, _ctx: QueueContext<()>) -> Result<()> {
+    if batch.queue() != "emails" {
+        return Err(Error::RustError(format!(
+            "emails handler got {}",
+            batch.queue()
+        )));
+    }
+    for message in batch.messages() {
+        message
+            .body()
+            .map_err(|e| Error::RustError(e.to_string()))?;
+        message.retry();
+    }
+    Ok(())
+}
+
+async fn route(batch: worker_sys::MessageBatch) -> Result<()> {
+    QueueRouter::new()
+        .on("orders", orders)
+        .on_async("emails", emails)
+        .run(
+            batch,
+            Object::new().unchecked_into::<Env>(),
+            Context::new(Object::new().unchecked_into()),
+        )
+        .await
+}
+
+#[wasm_bindgen_test]
+async fn router_dispatches_batches_by_queue() {
+    // Arrange
+    let id = Uuid::new_v4();
+    let (orders, orders_outcome) = MessageBatchBuilder::new("orders")
+        .message(&QueueBody {
+            id,
+            id_string: id.to_string(),
+        })
+        .unwrap()
+        .build();
+    let (emails, emails_outcome) = MessageBatchBuilder::new("emails")
+        .message(&"hello@example.com")
+        .unwrap()
+        .build();
+
+    // Act
+    route(orders).await.unwrap();
+    route(emails).await.unwrap();
+
+    // Assert
+    assert_eq!(orders_outcome.acked(), vec!["0"]);
+    assert!(orders_outcome.retried().is_empty());
+    assert_eq!(emails_outcome.retried(), vec!["0"]);
+    assert!(emails_outcome.acked().is_empty());
+}
+
+#[wasm_bindgen_test]
+async fn router_rejects_unknown_queues() {
+    // Arrange
+    let (batch, outcome) = MessageBatchBuilder::new("unknown")
+        .message(&"hello@example.com")
+        .unwrap()
+        .build();
+
+    // Act
+    let result = route(batch).await;
+
+    // Assert
+    assert!(result.is_err());
+    assert_eq!(outcome.unhandled(), vec!["0"]);
+}
@@ -503,6 +503,32 @@ fn r2_get() {
     assert_eq!(body, "ok");
 }
 
+#[test]
+fn r2_object_response() {
+    let response = get("r2/object/with-props", |r| r);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "text/text");
+    assert_eq!(response.headers()["content-language"], "en-US");
+    assert!(response.headers().contains_key("etag"));
+}
+
+#[test]
+fn r2_serve() {
+    let response = get("r2/serve/no-props", |r| r);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["accept-ranges"], "bytes");
+    let etag = response.headers()["etag"].to_str().unwrap().to_owned();
+    assert_eq!(response.text().unwrap(), "text");
+
+    let response = get("r2/serve/no-props", |r| r.header("range", "bytes=1-2"));
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.headers()["content-range"], "bytes 1-2/4");
+    assert_eq!(response.text().unwrap(), "ex");
+
+    let response = get("r2/serve/no-props", |r| r.header("if-none-match", &etag));
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+}
+
 #[test]
 fn r2_put() {
     let body = put("r2/put", |r| r).text().unwrap();
@@ -515,8 +541,290 @@ fn r2_put_with_properties() {
     assert_eq!(body, "ok");
 }
 
+#[test]
+fn r2_put_multipart_writer() {
+    // Two full 5MiB parts and a smaller last one.
+    let body = vec![7u8; 2 * 5 * 1024 * 1024 + 100];
+    let response = put("r2/put-multipart-writer", |r| r.body(body));
+    assert_eq!(
+        response.text().unwrap(),
+        (2 * 5 * 1024 * 1024 + 100).to_string()
+    );
+}
+
+#[test]
+fn r2_put_multipart_writer_copy() {
+    let body = vec![7u8; 5 * 1024 * 1024 + 100];
+    let response = put("r2/put-multipart-writer-copy", |r| r.body(body));
+    assert_eq!(
+        response.text().unwrap(),
+        (5 * 1024 * 1024 + 100).to_string()
+    );
+}
+
+#[test]
+fn r2_resumable_upload() {
+    let upload: serde_json::Value = post("r2/uploads/resumable?uploads", |r| r).json().unwrap();
+    let upload_id = upload["uploadId"].as_str().unwrap();
+    let endpoint = format!("r2/uploads/resumable?uploadId={upload_id}");
+
+    // Parts may be uploaded in any order.
+    let response = put(&format!("{endpoint}&partNumber=2"), |r| {
+        r.body(vec![2u8; 100])
+    });
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Completing fails while a part is missing.
+    let response = Client::new()
+        .post(format!("http://127.0.0.1:8787/{endpoint}"))
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = put(&format!("{endpoint}&partNumber=1"), |r| {
+        r.body(vec![1u8; 5 * 1024 * 1024])
+    });
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let listed: serde_json::Value = get(&endpoint, |r| r).json().unwrap();
+    let parts = listed["parts"].as_array().unwrap();
+    assert_eq!(parts.len(), 2);
+    assert_eq!(parts[0]["partNumber"], 1);
+    assert_eq!(parts[1]["size"], 100);
+
+    let completed: serde_json::Value = post(&endpoint, |r| r).json().unwrap();
+    assert_eq!(completed["size"], 5 * 1024 * 1024 + 100);
+
+    // The upload no longer exists once completed.
+    let response = Client::new()
+        .get(format!("http://127.0.0.1:8787/{endpoint}"))
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn r2_resumable_upload_rejects_state_keys() {
+    expect_wrangler();
+
+    let response = Client::new()
+        .post("http://127.0.0.1:8787/r2/uploads/.multipart-uploads/forged?uploads")
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = Client::new()
+        .put("http://127.0.0.1:8787/r2/uploads/.multipart-uploads/forged?uploadId=forged&partNumber=1")
+        .body(vec![1u8; 100])
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[test]
+fn http_roundtrip() {
+    let response = post("http-roundtrip", |r| r.body("hello"));
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(response.headers()["x-reversed"], "true");
+    assert_eq!(response.text().unwrap(), "olleh");
+}
+
+#[test]
+fn http_request_conversion() {
+    // The empty body of the converted GET request must not be passed to the runtime.
+    let body = get("http-request-conversion", |r| r).text().unwrap();
+    assert_eq!(body, "GET true false");
+}
+
+#[test]
+fn tower_service() {
+    let body = get("tower/hello", |r| r).text().unwrap();
+    assert_eq!(body, "/tower/hello some value");
+}
+
+#[test]
+fn response_builder() {
+    let response = get("response-builder", |r| r);
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let custom: Vec<_> = response
+        .headers()
+        .get_all("x-custom")
+        .iter()
+        .map(|v| v.to_str().unwrap())
+        .collect();
+    assert_eq!(custom.join(", "), "one, two");
+    assert_eq!(response.headers()["cache-control"], "no-store");
+    assert_eq!(response.headers()["content-type"], "application/json");
+
+    let body: serde_json::Value = response.json().unwrap();
+    assert_eq!(body["built"], true);
+}
+
+#[test]
+fn into_response() {
+    let response = get("into-response/202", |r| r);
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    assert_eq!(response.text().unwrap(), "status 202");
+}
+
+#[test]
+fn cookies() {
+    let response = get("cookies", |r| r.header("Cookie", "theme=dark; visits=2"));
+    let set_cookies: Vec<_> = response
+        .headers()
+        .get_all("set-cookie")
+        .iter()
+        .map(|v| v.to_str().unwrap().to_owned())
+        .collect();
+    assert_eq!(
+        set_cookies,
+        [
+            "visits=3; Path=/",
+            "session=abc; Max-Age=60; SameSite=Strict; Secure; HttpOnly"
+        ]
+    );
+    assert_eq!(response.text().unwrap(), "visits: 2");
+}
+
+#[test]
+fn set_cookies() {
+    let body: serde_json::Value = get("set-cookies", |r| r).json().unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "setCookies": ["first=1", "second=2; Path=/"],
+            "injectionRejected": true,
+        })
+    );
+}
+
+#[test]
+fn session() {
+    let response = get("session", |r| r);
+    let set_cookie = response.headers()["set-cookie"].to_str().unwrap();
+    let session = set_cookie.split(';').next().unwrap().to_owned();
+    assert!(set_cookie.contains("Max-Age=60"));
+    assert_eq!(response.text().unwrap(), "visits: 1");
+
+    let response = get("session", |r| r.header("Cookie", &session));
+    assert_eq!(response.text().unwrap(), "visits: 2");
+
+    // Tampered sessions are ignored.
+    let tampered = session.replacen("session=", "session=x", 1);
+    let response = get("session", |r| r.header("Cookie", &tampered));
+    assert_eq!(response.text().unwrap(), "visits: 1");
+
+    // Sessions are bound to the name of their cookie.
+    let renamed = session.replacen("session=", "other=", 1);
+    let response = get("session?name=other", |r| r.header("Cookie", &renamed));
+    assert_eq!(response.text().unwrap(), "visits: 1");
+}
+
+#[test]
+fn sse() {
+    let response = get("sse", |r| r);
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+    assert_eq!(response.headers()["cache-control"], "no-cache");
+
+    let body = response.text().unwrap();
+    let events: Vec<_> = body
+        .split("\n\n")
+        .filter(|event| !event.is_empty() && !event.starts_with(':'))
+        .collect();
+    assert_eq!(
+        events,
+        [
+            "event: progress\nid: 1\ndata: {\"progress\":1}",
+            "event: progress\nid: 2\ndata: {\"progress\":2}",
+            "event: progress\nid: 3\ndata: {\"progress\":3}",
+            "data: done\ndata: bye",
+        ]
+    );
+    assert!(body.contains(": \n\n"), "expected a keep-alive comment");
+}
+
+#[test]
+fn body_limit() {
+    let response = post("body-limit", |r| r.body("short enough"));
+    assert_eq!(response.text().unwrap(), "short enough");
+
+    let response = Client::new()
+        .post("http://127.0.0.1:8787/body-limit")
+        .body("this body is longer than sixteen bytes")
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+    let response = Client::new()
+        .post("http://127.0.0.1:8787/body-limit-clone")
+        .body("this body is longer than sixteen bytes")
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[test]
+fn request_init_body() {
+    let sent: Vec<String> = get("request-init-body", |r| r).json().unwrap();
+    assert_eq!(
+        sent,
+        [
+            "application/json {\"a\":1}",
+            "text/plain;charset=UTF-8 text",
+            "application/x-www-form-urlencoded a=1&b=2",
+            "application/octet-stream ab",
+        ]
+    );
+}
+
+#[test]
+fn request_init_abort() {
+    let body = get("request-init-abort", |r| r).text().unwrap();
+    assert_eq!(body, "aborted");
+}
+
+#[test]
+fn request_init_options() {
+    let body: serde_json::Value = get("request-init-options", |r| r).json().unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({ "cache": "no-store", "integrityRejected": true })
+    );
+}
+
+#[test]
+fn extension_method() {
+    expect_wrangler();
+
+    let purge = http::Method::from_bytes(b"PURGE").unwrap();
+    let response = Client::new()
+        .request(purge, "http://127.0.0.1:8787/purge/cached")
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text().unwrap(), "PURGE cached");
+
+    let response = Client::new()
+        .get("http://127.0.0.1:8787/purge/cached")
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+}
+
+#[test]
+fn r2_json() {
+    let body = put("r2/json", |r| r).text().unwrap();
+    assert_eq!(body, "ok");
+}
+
 #[test]
 fn r2_delete() {
     let body = delete("r2/delete", |r| r).text().unwrap();
     assert_eq!(body, "ok");
 }
+
+#[test]
+fn r2_delete_many() {
+    let body = delete("r2/delete-many", |r| r).text().unwrap();
+    assert_eq!(body, "ok");
+}
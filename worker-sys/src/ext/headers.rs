@@ -16,6 +16,9 @@ mod glue {
 
         #[wasm_bindgen(catch, method, structural, js_class=Headers, js_name=values)]
         pub fn values(this: &HeadersExt) -> Result<js_sys::Iterator, JsValue>;
+
+        #[wasm_bindgen(method, structural, js_class=Headers, js_name=getSetCookie)]
+        pub fn get_set_cookie(this: &HeadersExt) -> js_sys::Array;
     }
 }
 
@@ -25,6 +28,8 @@ pub trait HeadersExt {
     fn keys(&self) -> Result<js_sys::Iterator, JsValue>;
 
     fn values(&self) -> Result<js_sys::Iterator, JsValue>;
+
+    fn get_set_cookie(&self) -> js_sys::Array;
 }
 
 impl HeadersExt for web_sys::Headers {
@@ -39,4 +44,8 @@ impl HeadersExt for web_sys::Headers {
     fn values(&self) -> Result<js_sys::Iterator, JsValue> {
         self.unchecked_ref::<glue::HeadersExt>().values()
     }
+
+    fn get_set_cookie(&self) -> js_sys::Array {
+        self.unchecked_ref::<glue::HeadersExt>().get_set_cookie()
+    }
 }
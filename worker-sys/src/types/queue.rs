@@ -14,6 +14,40 @@ extern "C" {
 
     #[wasm_bindgen(structural, method, js_class=MessageBatch, js_name=retryAll)]
     pub fn retry_all(this: &MessageBatch);
+
+    #[wasm_bindgen(structural, method, js_class=MessageBatch, js_name=retryAll)]
+    pub fn retry_all_with_options(this: &MessageBatch, options: JsValue);
+
+    #[wasm_bindgen(structural, method, js_class=MessageBatch, js_name=ackAll)]
+    pub fn ack_all(this: &MessageBatch);
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends=js_sys::Object, js_name=Message)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub type Message;
+
+    #[wasm_bindgen(method, getter, js_class=Message, js_name=id)]
+    pub fn id(this: &Message) -> String;
+
+    #[wasm_bindgen(method, getter, js_class=Message, js_name=timestamp)]
+    pub fn timestamp(this: &Message) -> js_sys::Date;
+
+    #[wasm_bindgen(method, getter, js_class=Message, js_name=body)]
+    pub fn body(this: &Message) -> JsValue;
+
+    #[wasm_bindgen(method, getter, js_class=Message, js_name=attempts)]
+    pub fn attempts(this: &Message) -> u32;
+
+    #[wasm_bindgen(structural, method, js_class=Message, js_name=ack)]
+    pub fn ack(this: &Message);
+
+    #[wasm_bindgen(structural, method, js_class=Message, js_name=retry)]
+    pub fn retry(this: &Message);
+
+    #[wasm_bindgen(structural, method, js_class=Message, js_name=retry)]
+    pub fn retry_with_options(this: &Message, options: JsValue);
 }
 
 #[wasm_bindgen]
@@ -24,4 +58,10 @@ extern "C" {
 
     #[wasm_bindgen(structural, method, js_class=Queue, js_name=send)]
     pub fn send(this: &Queue, mesage: JsValue) -> js_sys::Promise;
+
+    #[wasm_bindgen(structural, method, js_class=Queue, js_name=send)]
+    pub fn send_with_options(this: &Queue, message: JsValue, options: JsValue) -> js_sys::Promise;
+
+    #[wasm_bindgen(structural, method, js_class=Queue, js_name=sendBatch)]
+    pub fn send_batch(this: &Queue, messages: JsValue) -> js_sys::Promise;
 }
@@ -18,6 +18,9 @@ extern "C" {
     #[wasm_bindgen(structural, method, js_class=R2Bucket, js_name=delete)]
     pub fn delete(this: &R2Bucket, key: String) -> js_sys::Promise;
 
+    #[wasm_bindgen(structural, method, js_class=R2Bucket, js_name=delete)]
+    pub fn delete_multiple(this: &R2Bucket, keys: Vec<JsValue>) -> js_sys::Promise;
+
     #[wasm_bindgen(structural, method, js_class=R2Bucket, js_name=list)]
     pub fn list(this: &R2Bucket, options: JsValue) -> js_sys::Promise;
 
@@ -0,0 +1,23 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends=js_sys::Object, js_name=R2Checksums)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub type R2Checksums;
+
+    #[wasm_bindgen(structural, method, getter, js_class=R2Checksums, js_name=md5)]
+    pub fn md5(this: &R2Checksums) -> Option<js_sys::ArrayBuffer>;
+
+    #[wasm_bindgen(structural, method, getter, js_class=R2Checksums, js_name=sha1)]
+    pub fn sha1(this: &R2Checksums) -> Option<js_sys::ArrayBuffer>;
+
+    #[wasm_bindgen(structural, method, getter, js_class=R2Checksums, js_name=sha256)]
+    pub fn sha256(this: &R2Checksums) -> Option<js_sys::ArrayBuffer>;
+
+    #[wasm_bindgen(structural, method, getter, js_class=R2Checksums, js_name=sha384)]
+    pub fn sha384(this: &R2Checksums) -> Option<js_sys::ArrayBuffer>;
+
+    #[wasm_bindgen(structural, method, getter, js_class=R2Checksums, js_name=sha512)]
+    pub fn sha512(this: &R2Checksums) -> Option<js_sys::ArrayBuffer>;
+}
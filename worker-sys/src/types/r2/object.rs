@@ -1,6 +1,6 @@
 use wasm_bindgen::prelude::*;
 
-use crate::types::{R2HttpMetadata, R2Range};
+use crate::types::{R2Checksums, R2HttpMetadata, R2Range};
 
 #[wasm_bindgen]
 extern "C" {
@@ -15,7 +15,7 @@ extern "C" {
     pub fn version(this: &R2Object) -> String;
 
     #[wasm_bindgen(structural, method, getter, js_class=R2Object, js_name=size)]
-    pub fn size(this: &R2Object) -> u32;
+    pub fn size(this: &R2Object) -> f64;
 
     #[wasm_bindgen(structural, method, getter, js_class=R2Object, js_name=etag)]
     pub fn etag(this: &R2Object) -> String;
@@ -32,8 +32,14 @@ extern "C" {
     #[wasm_bindgen(structural, method, getter, js_class=R2Object, js_name=customMetadata)]
     pub fn custom_metadata(this: &R2Object) -> js_sys::Object;
 
+    #[wasm_bindgen(structural, method, getter, js_class=R2Object, js_name=storageClass)]
+    pub fn storage_class(this: &R2Object) -> Option<String>;
+
+    #[wasm_bindgen(structural, method, getter, js_class=R2Object, js_name=checksums)]
+    pub fn checksums(this: &R2Object) -> R2Checksums;
+
     #[wasm_bindgen(structural, method, getter, js_class=R2Object, js_name=range)]
-    pub fn range(this: &R2Object) -> R2Range;
+    pub fn range(this: &R2Object) -> Option<R2Range>;
 
     #[wasm_bindgen(structural, method, js_class=R2Object, js_name=writeHttpMetadata, catch)]
     pub fn write_http_metadata(
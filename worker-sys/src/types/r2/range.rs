@@ -1,9 +1,17 @@
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
-#[derive(Debug, Clone)]
-pub struct R2Range {
-    pub offset: Option<u32>,
-    pub length: Option<u32>,
-    pub suffix: Option<u32>,
+extern "C" {
+    #[wasm_bindgen(extends=js_sys::Object, js_name=R2Range)]
+    #[derive(Debug, Clone, PartialEq)]
+    pub type R2Range;
+
+    #[wasm_bindgen(structural, method, getter, js_class=R2Range, js_name=offset)]
+    pub fn offset(this: &R2Range) -> Option<f64>;
+
+    #[wasm_bindgen(structural, method, getter, js_class=R2Range, js_name=length)]
+    pub fn length(this: &R2Range) -> Option<f64>;
+
+    #[wasm_bindgen(structural, method, getter, js_class=R2Range, js_name=suffix)]
+    pub fn suffix(this: &R2Range) -> Option<f64>;
 }
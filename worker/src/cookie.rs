@@ -0,0 +1,284 @@
+use std::{collections::HashMap, fmt};
+
+use js_sys::Date as JsDate;
+use wasm_bindgen::JsValue;
+
+use crate::{Date, Error, Result};
+
+/// A cookie to be set on a client with a
+/// [Set-Cookie](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Set-Cookie) header,
+/// formatted by its [Display](fmt::Display) implementation.
+///
+/// The name, value, domain and path are validated when set, so that they can't end the
+/// `Set-Cookie` header early or inject other attributes into it.
+///
+/// ```no_run
+/// let cookie = Cookie::new("session", "abc123")?
+///     .with_path("/")?
+///     .with_max_age(3600)
+///     .with_same_site(SameSite::Lax)
+///     .with_secure(true)
+///     .with_http_only(true);
+/// Response::ok("signed in")?.with_cookie(&cookie)
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cookie {
+    name: String,
+    value: String,
+    domain: Option<String>,
+    path: Option<String>,
+    max_age: Option<i64>,
+    expires: Option<Date>,
+    same_site: Option<SameSite>,
+    secure: bool,
+    http_only: bool,
+}
+
+impl Cookie {
+    /// Creates a cookie with the given name and value, without any attributes.
+    ///
+    /// Fails unless the name is an [RFC 6265](https://www.rfc-editor.org/rfc/rfc6265#section-4.1.1)
+    /// token, and the value only consists of cookie octets, optionally enclosed in double quotes.
+    /// Values which may contain other characters, such as whitespace, `;` or `,`, must be encoded
+    /// beforehand.
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Result<Self> {
+        let name = name.into();
+        let value = value.into();
+        if !is_token(&name) {
+            return Err(Error::RustError(format!("invalid cookie name `{name}`")));
+        }
+        if !is_cookie_value(&value) {
+            return Err(Error::RustError(format!(
+                "invalid value for cookie `{name}`"
+            )));
+        }
+
+        Ok(Self {
+            name,
+            value,
+            domain: None,
+            path: None,
+            max_age: None,
+            expires: None,
+            same_site: None,
+            secure: false,
+            http_only: false,
+        })
+    }
+
+    /// Creates a cookie which removes the cookie with the given name from the client when set.
+    /// Its path and domain must match those of the cookie being removed.
+    pub fn removal(name: impl Into<String>) -> Result<Self> {
+        Ok(Self::new(name, "")?.with_max_age(0))
+    }
+
+    /// The name of the cookie.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The value of the cookie.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Sets the `Domain` attribute, the host to which the cookie will be sent. Fails if the
+    /// domain contains control characters or `;`.
+    pub fn with_domain(mut self, domain: impl Into<String>) -> Result<Self> {
+        self.domain = Some(attribute_value("Domain", domain.into())?);
+        Ok(self)
+    }
+
+    /// Sets the `Path` attribute, the path that must exist in the requested URL for the cookie to
+    /// be sent. Fails if the path contains control characters or `;`.
+    pub fn with_path(mut self, path: impl Into<String>) -> Result<Self> {
+        self.path = Some(attribute_value("Path", path.into())?);
+        Ok(self)
+    }
+
+    /// Sets the `Max-Age` attribute, the number of seconds until the cookie expires. Zero or a
+    /// negative number expires the cookie immediately.
+    pub fn with_max_age(mut self, seconds: i64) -> Self {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    /// Sets the `Expires` attribute, the date at which the cookie expires. `Max-Age` takes
+    /// precedence if both are set.
+    pub fn with_expires(mut self, expires: Date) -> Self {
+        self.expires = Some(expires);
+        self
+    }
+
+    /// Sets the `SameSite` attribute, controlling whether the cookie is sent with cross-site
+    /// requests.
+    pub fn with_same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self
+    }
+
+    /// Sets the `Secure` attribute, which only sends the cookie over HTTPS.
+    pub fn with_secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Sets the `HttpOnly` attribute, which forbids JavaScript from accessing the cookie.
+    pub fn with_http_only(mut self, http_only: bool) -> Self {
+        self.http_only = http_only;
+        self
+    }
+}
+
+impl fmt::Display for Cookie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.name, self.value)?;
+        if let Some(domain) = &self.domain {
+            write!(f, "; Domain={domain}")?;
+        }
+        if let Some(path) = &self.path {
+            write!(f, "; Path={path}")?;
+        }
+        if let Some(max_age) = self.max_age {
+            write!(f, "; Max-Age={max_age}")?;
+        }
+        if let Some(expires) = &self.expires {
+            let date = JsDate::new(&JsValue::from_f64(expires.as_millis() as f64));
+            write!(f, "; Expires={}", String::from(date.to_utc_string()))?;
+        }
+        if let Some(same_site) = self.same_site {
+            write!(f, "; SameSite={}", same_site.as_str())?;
+        }
+        if self.secure {
+            f.write_str("; Secure")?;
+        }
+        if self.http_only {
+            f.write_str("; HttpOnly")?;
+        }
+        Ok(())
+    }
+}
+
+/// The value of the `SameSite` attribute of a [Cookie].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    /// The cookie is only sent with same-site requests.
+    Strict,
+    /// The cookie is also sent when navigating to the site from another site.
+    Lax,
+    /// The cookie is sent with all requests, which requires the `Secure` attribute.
+    None,
+}
+
+impl SameSite {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Strict => "Strict",
+            Self::Lax => "Lax",
+            Self::None => "None",
+        }
+    }
+}
+
+/// Whether `name` is a token, made of visible ASCII characters other than separators.
+fn is_token(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_graphic() && !b"()<>@,;:\\\"/[]?={}".contains(&b))
+}
+
+/// Whether `value` only consists of cookie octets, optionally enclosed in double quotes.
+fn is_cookie_value(value: &str) -> bool {
+    let value = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value);
+    value
+        .bytes()
+        .all(|b| b.is_ascii_graphic() && !matches!(b, b'"' | b',' | b';' | b'\\'))
+}
+
+/// Checks that an attribute value contains no control characters and no `;`, either of which
+/// would end the attribute early.
+fn attribute_value(attribute: &str, value: String) -> Result<String> {
+    if value
+        .bytes()
+        .all(|b| (b' '..=b'~').contains(&b) && b != b';')
+    {
+        Ok(value)
+    } else {
+        Err(Error::RustError(format!(
+            "invalid value for cookie attribute `{attribute}`"
+        )))
+    }
+}
+
+/// Parses the value of a `Cookie` request header into its name/value pairs. When a name appears
+/// more than once, its first value is kept, as it is the one with the most specific path.
+pub(crate) fn parse_cookie_header(header: &str) -> HashMap<String, String> {
+    let mut cookies = HashMap::new();
+    for pair in header.split(';') {
+        let (name, value) = match pair.split_once('=') {
+            Some((name, value)) => (name.trim(), value.trim()),
+            None => continue,
+        };
+        if name.is_empty() {
+            continue;
+        }
+
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+        cookies
+            .entry(name.to_string())
+            .or_insert_with(|| value.to_string());
+    }
+    cookies
+}
+
+#[test]
+fn parses_cookie_header() {
+    let cookies = parse_cookie_header(r#"a=1; b = "two" ;invalid; a=3; c=x=y"#);
+    assert_eq!(cookies.len(), 3);
+    assert_eq!(cookies["a"], "1");
+    assert_eq!(cookies["b"], "two");
+    assert_eq!(cookies["c"], "x=y");
+}
+
+#[test]
+fn formats_set_cookie() {
+    let cookie = Cookie::new("session", "abc")
+        .unwrap()
+        .with_domain("example.com")
+        .unwrap()
+        .with_path("/")
+        .unwrap()
+        .with_max_age(60)
+        .with_same_site(SameSite::Strict)
+        .with_secure(true)
+        .with_http_only(true);
+    assert_eq!(
+        cookie.to_string(),
+        "session=abc; Domain=example.com; Path=/; Max-Age=60; SameSite=Strict; Secure; HttpOnly"
+    );
+    assert_eq!(
+        Cookie::removal("session").unwrap().to_string(),
+        "session=; Max-Age=0"
+    );
+}
+
+#[test]
+fn rejects_attribute_injection() {
+    assert!(Cookie::new("session", "a; Domain=evil").is_err());
+    assert!(Cookie::new("session", "a,b").is_err());
+    assert!(Cookie::new("session", "a\r\nSet-Cookie: b=c").is_err());
+    assert!(Cookie::new("session id", "abc").is_err());
+    assert!(Cookie::new("", "abc").is_err());
+    assert!(Cookie::new("session", "\"quoted\"").is_ok());
+
+    let cookie = Cookie::new("session", "abc").unwrap();
+    assert!(cookie.clone().with_path("/; Max-Age=0").is_err());
+    assert!(cookie.with_domain("example.com\n").is_err());
+}
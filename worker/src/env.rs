@@ -1,7 +1,7 @@
 use crate::error::Error;
-#[cfg(feature = "queue")]
-use crate::Queue;
 use crate::{durable::ObjectNamespace, Bucket, DynamicDispatcher, Fetcher, Result};
+#[cfg(feature = "queue")]
+use crate::{Queue, TypedQueue};
 
 use js_sys::Object;
 use wasm_bindgen::{prelude::*, JsCast, JsValue};
@@ -10,6 +10,7 @@ use worker_kv::KvStore;
 #[wasm_bindgen]
 extern "C" {
     /// Env contains any bindings you have associated with the Worker when you uploaded it.
+    #[derive(Clone)]
     pub type Env;
 }
 
@@ -65,6 +66,13 @@ impl Env {
         self.get_binding(binding)
     }
 
+    #[cfg(feature = "queue")]
+    /// Access a Queue by the binding name configured in your wrangler.toml file, restricting the
+    /// messages sent to it to type `T`.
+    pub fn typed_queue<T>(&self, binding: &str) -> Result<TypedQueue<T>> {
+        self.queue(binding).map(TypedQueue::from)
+    }
+
     /// Access an R2 Bucket by the binding name configured in your wrangler.toml file.
     pub fn bucket(&self, binding: &str) -> Result<Bucket> {
         self.get_binding(binding)
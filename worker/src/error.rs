@@ -6,6 +6,8 @@ use wasm_bindgen::{JsCast, JsValue};
 pub enum Error {
     BadEncoding,
     BodyUsed,
+    /// The body of a request exceeded the limit, in bytes, set on the request.
+    BodyTooLarge(u64),
     Json((String, u16)),
     JsError(String),
     Internal(JsValue),
@@ -43,6 +45,7 @@ impl std::fmt::Display for Error {
         match self {
             Error::BadEncoding => write!(f, "content-type mismatch"),
             Error::BodyUsed => write!(f, "body has already been read"),
+            Error::BodyTooLarge(limit) => write!(f, "body exceeds the limit of {limit} bytes"),
             Error::Json((msg, status)) => write!(f, "{msg} (status: {status})"),
             Error::JsError(s) | Error::RustError(s) => {
                 write!(f, "{s}")
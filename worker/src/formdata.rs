@@ -99,6 +99,12 @@ impl From<JsValue> for FormData {
     }
 }
 
+impl From<FormData> for JsValue {
+    fn from(form: FormData) -> Self {
+        form.0.into()
+    }
+}
+
 impl From<HashMap<&dyn AsRef<&str>, &dyn AsRef<&str>>> for FormData {
     fn from(m: HashMap<&dyn AsRef<&str>, &dyn AsRef<&str>>) -> Self {
         let mut formdata = FormData::new();
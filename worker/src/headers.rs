@@ -1,11 +1,13 @@
 use crate::{error::Error, Result};
 
+#[cfg(feature = "http")]
+use std::str::FromStr;
 use std::{
     iter::{FromIterator, Map},
     result::Result as StdResult,
-    str::FromStr,
 };
 
+#[cfg(feature = "http")]
 use http::{header::HeaderName, HeaderMap, HeaderValue};
 use js_sys::Array;
 use wasm_bindgen::JsValue;
@@ -61,6 +63,16 @@ impl Headers {
         self.0.delete(name).map_err(Error::from)
     }
 
+    /// Returns the values of all `Set-Cookie` headers, which unlike other headers cannot be
+    /// combined into a single value by [get](Self::get).
+    pub fn get_all_set_cookie(&self) -> Vec<String> {
+        self.0
+            .get_set_cookie()
+            .iter()
+            .filter_map(|value| value.as_string())
+            .collect()
+    }
+
     /// Returns an iterator allowing to go through all key/value pairs contained in this object.
     pub fn entries(&self) -> HeaderIterator {
         self.0
@@ -145,6 +157,7 @@ impl AsRef<JsValue> for Headers {
     }
 }
 
+#[cfg(feature = "http")]
 impl From<&HeaderMap> for Headers {
     fn from(map: &HeaderMap) -> Self {
         map.keys()
@@ -157,12 +170,14 @@ impl From<&HeaderMap> for Headers {
     }
 }
 
+#[cfg(feature = "http")]
 impl From<HeaderMap> for Headers {
     fn from(map: HeaderMap) -> Self {
         (&map).into()
     }
 }
 
+#[cfg(feature = "http")]
 impl From<&Headers> for HeaderMap {
     fn from(headers: &Headers) -> Self {
         headers
@@ -177,6 +192,7 @@ impl From<&Headers> for HeaderMap {
     }
 }
 
+#[cfg(feature = "http")]
 impl From<Headers> for HeaderMap {
     fn from(headers: Headers) -> Self {
         (&headers).into()
@@ -0,0 +1,136 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_util::{Stream, TryStreamExt};
+use js_sys::Uint8Array;
+use pin_project::pin_project;
+use wasm_bindgen::JsValue;
+
+use crate::{streams::to_readable_stream, ByteStream, Error, ResponseBody, Result};
+
+/// The body of [`http::Request`]s and [`http::Response`]s converted from a [Request](crate::Request)
+/// or a [Response](crate::Response), and accepted when converting them back.
+///
+/// Bodies converted from a `Request` or `Response` stream their data from the runtime rather than
+/// buffering it, and can be consumed as a [Stream] of byte chunks or read as a whole with
+/// [bytes](Self::bytes) and [text](Self::text).
+#[pin_project]
+#[derive(Debug)]
+pub struct HttpBody {
+    #[pin]
+    inner: BodyInner,
+}
+
+#[pin_project(project = BodyInnerProj)]
+#[derive(Debug)]
+enum BodyInner {
+    Empty,
+    Bytes(Option<Vec<u8>>),
+    Stream(#[pin] ByteStream),
+}
+
+impl HttpBody {
+    /// A body without any data.
+    pub fn empty() -> Self {
+        Self {
+            inner: BodyInner::Empty,
+        }
+    }
+
+    /// Reads the whole body.
+    pub async fn bytes(self) -> Result<Vec<u8>> {
+        match self.inner {
+            BodyInner::Empty => Ok(Vec::new()),
+            BodyInner::Bytes(bytes) => Ok(bytes.unwrap_or_default()),
+            BodyInner::Stream(stream) => stream.try_concat().await,
+        }
+    }
+
+    /// Reads the whole body as UTF-8 text.
+    pub async fn text(self) -> Result<String> {
+        String::from_utf8(self.bytes().await?).map_err(|e| Error::RustError(e.to_string()))
+    }
+
+    /// The body to create a request with, which is `None` for empty bodies since requests of
+    /// some methods, such as `GET`, can't have a body.
+    pub(crate) fn into_js(self) -> Option<JsValue> {
+        match self.inner {
+            BodyInner::Empty => None,
+            BodyInner::Bytes(bytes) => {
+                let bytes = bytes.filter(|bytes| !bytes.is_empty())?;
+                let array = Uint8Array::new_with_length(bytes.len() as _);
+                array.copy_from(&bytes);
+                Some(array.into())
+            }
+            BodyInner::Stream(stream) => Some(to_readable_stream(stream).into()),
+        }
+    }
+
+    pub(crate) fn into_response_body(self) -> ResponseBody {
+        match self.inner {
+            BodyInner::Empty => ResponseBody::Empty,
+            BodyInner::Bytes(bytes) => ResponseBody::Body(bytes.unwrap_or_default()),
+            BodyInner::Stream(stream) => ResponseBody::Stream(to_readable_stream(stream)),
+        }
+    }
+}
+
+impl Default for HttpBody {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl Stream for HttpBody {
+    type Item = Result<Vec<u8>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match self.project().inner.project() {
+            BodyInnerProj::Empty => Poll::Ready(None),
+            BodyInnerProj::Bytes(bytes) => Poll::Ready(bytes.take().map(Ok)),
+            BodyInnerProj::Stream(stream) => stream.poll_next(cx),
+        }
+    }
+}
+
+impl From<ByteStream> for HttpBody {
+    fn from(stream: ByteStream) -> Self {
+        Self {
+            inner: BodyInner::Stream(stream),
+        }
+    }
+}
+
+impl From<Vec<u8>> for HttpBody {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self {
+            inner: BodyInner::Bytes(Some(bytes)),
+        }
+    }
+}
+
+impl From<&[u8]> for HttpBody {
+    fn from(bytes: &[u8]) -> Self {
+        bytes.to_vec().into()
+    }
+}
+
+impl From<String> for HttpBody {
+    fn from(text: String) -> Self {
+        text.into_bytes().into()
+    }
+}
+
+impl From<&str> for HttpBody {
+    fn from(text: &str) -> Self {
+        text.as_bytes().into()
+    }
+}
+
+impl From<()> for HttpBody {
+    fn from(_: ()) -> Self {
+        Self::empty()
+    }
+}
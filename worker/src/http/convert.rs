@@ -0,0 +1,79 @@
+//! Conversions between the [Request] and [Response] types of this crate and those of the `http`
+//! crate, whose bodies are represented as [HttpBody].
+
+use std::convert::TryFrom;
+
+use crate::{Error, Headers, Method, Request, RequestInit, Response, ResponseBody, Result};
+
+use super::HttpBody;
+
+fn http_error(e: impl std::fmt::Display) -> Error {
+    Error::RustError(e.to_string())
+}
+
+impl TryFrom<Request> for ::http::Request<HttpBody> {
+    type Error = Error;
+
+    fn try_from(mut req: Request) -> Result<Self> {
+        let body = if req.inner().body().is_some() {
+            req.stream()?.into()
+        } else {
+            HttpBody::empty()
+        };
+
+        let mut http_req = ::http::Request::new(body);
+        *http_req.method_mut() =
+            ::http::Method::from_bytes(req.method().as_ref().as_bytes()).map_err(http_error)?;
+        *http_req.uri_mut() = req.url()?.as_str().parse().map_err(http_error)?;
+        *http_req.headers_mut() = req.headers().into();
+
+        Ok(http_req)
+    }
+}
+
+impl<B: Into<HttpBody>> TryFrom<::http::Request<B>> for Request {
+    type Error = Error;
+
+    /// Creates a [Request] from an [`http::Request`], whose URI must be absolute.
+    fn try_from(req: ::http::Request<B>) -> Result<Self> {
+        let (parts, body) = req.into_parts();
+
+        let mut init = RequestInit::new();
+        init.with_method(Method::from(parts.method.to_string()))
+            .with_headers(Headers::from(&parts.headers))
+            .with_body(body.into().into_js());
+
+        Request::new_with_init(&parts.uri.to_string(), &init)
+    }
+}
+
+impl TryFrom<Response> for ::http::Response<HttpBody> {
+    type Error = Error;
+
+    fn try_from(mut res: Response) -> Result<Self> {
+        let body = match res.body() {
+            ResponseBody::Empty => HttpBody::empty(),
+            ResponseBody::Body(bytes) => bytes.clone().into(),
+            ResponseBody::Stream(_) => res.stream()?.into(),
+        };
+
+        let mut http_res = ::http::Response::new(body);
+        *http_res.status_mut() =
+            ::http::StatusCode::from_u16(res.status_code()).map_err(http_error)?;
+        *http_res.headers_mut() = res.headers().into();
+
+        Ok(http_res)
+    }
+}
+
+impl<B: Into<HttpBody>> TryFrom<::http::Response<B>> for Response {
+    type Error = Error;
+
+    fn try_from(res: ::http::Response<B>) -> Result<Self> {
+        let (parts, body) = res.into_parts();
+
+        Ok(Response::from_body(body.into().into_response_body())?
+            .with_status(parts.status.as_u16())
+            .with_headers(Headers::from(&parts.headers)))
+    }
+}
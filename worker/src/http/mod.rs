@@ -1,8 +1,19 @@
+#[cfg(feature = "http")]
+mod body;
+#[cfg(feature = "http")]
+mod convert;
+
+#[cfg(feature = "http")]
+pub use body::HttpBody;
+
 /// A [`Method`](https://developer.mozilla.org/en-US/docs/Web/API/Request/method) representation
 /// used on Request objects.
+///
+/// Methods that are not standard, such as WebDAV's `PROPFIND` or `PURGE`, are represented as
+/// [Method::Other] with their original spelling, since extension methods are case-sensitive.
 #[derive(Debug, Clone, PartialEq, Hash, Eq)]
 pub enum Method {
-    Head = 0,
+    Head,
     Get,
     Post,
     Put,
@@ -11,9 +22,13 @@ pub enum Method {
     Options,
     Connect,
     Trace,
+    /// An extension method. Prefer creating methods with [From], which maps the standard methods
+    /// to their own variants.
+    Other(String),
 }
 
 impl Method {
+    /// The standard methods, which does not include any [Method::Other].
     pub fn all() -> Vec<Method> {
         vec![
             Method::Head,
@@ -33,6 +48,7 @@ impl From<String> for Method {
     fn from(m: String) -> Self {
         match m.to_ascii_uppercase().as_str() {
             "HEAD" => Method::Head,
+            "GET" => Method::Get,
             "POST" => Method::Post,
             "PUT" => Method::Put,
             "PATCH" => Method::Patch,
@@ -40,19 +56,28 @@ impl From<String> for Method {
             "OPTIONS" => Method::Options,
             "CONNECT" => Method::Connect,
             "TRACE" => Method::Trace,
-            _ => Method::Get,
+            _ => Method::Other(m),
         }
     }
 }
 
+impl From<&str> for Method {
+    fn from(m: &str) -> Self {
+        m.to_string().into()
+    }
+}
+
 impl From<Method> for String {
     fn from(val: Method) -> Self {
-        val.as_ref().to_string()
+        match val {
+            Method::Other(m) => m,
+            _ => val.as_ref().to_string(),
+        }
     }
 }
 
 impl AsRef<str> for Method {
-    fn as_ref(&self) -> &'static str {
+    fn as_ref(&self) -> &str {
         match self {
             Method::Head => "HEAD",
             Method::Post => "POST",
@@ -63,6 +88,7 @@ impl AsRef<str> for Method {
             Method::Connect => "CONNECT",
             Method::Trace => "TRACE",
             Method::Get => "GET",
+            Method::Other(m) => m,
         }
     }
 }
@@ -78,3 +104,13 @@ impl Default for Method {
         Method::Get
     }
 }
+
+#[test]
+fn method_round_trips() {
+    for method in Method::all() {
+        assert_eq!(Method::from(method.to_string()), method);
+    }
+    assert_eq!(Method::from("delete"), Method::Delete);
+    assert_eq!(Method::from("PROPFIND"), Method::Other("PROPFIND".into()));
+    assert_eq!(String::from(Method::from("Purge")), "Purge");
+}
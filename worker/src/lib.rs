@@ -24,6 +24,7 @@ pub use worker_sys::{console_debug, console_error, console_log, console_warn};
 pub use crate::abort::*;
 pub use crate::cache::{Cache, CacheDeletionOutcome};
 pub use crate::context::Context;
+pub use crate::cookie::{Cookie, SameSite};
 pub use crate::cors::Cors;
 pub use crate::date::{Date, DateInit};
 pub use crate::delay::Delay;
@@ -35,15 +36,20 @@ pub use crate::fetcher::Fetcher;
 pub use crate::formdata::*;
 pub use crate::global::Fetch;
 pub use crate::headers::Headers;
+#[cfg(feature = "http")]
+pub use crate::http::HttpBody;
 pub use crate::http::Method;
 #[cfg(feature = "queue")]
 pub use crate::queue::*;
+#[cfg(feature = "queue")]
+pub use crate::queue_router::{QueueContext, QueueRouter};
 pub use crate::r2::*;
 pub use crate::request::Request;
 pub use crate::request_init::*;
-pub use crate::response::{Response, ResponseBody};
+pub use crate::response::{IntoResponse, Response, ResponseBody, ResponseBuilder};
 pub use crate::router::{RouteContext, RouteParams, Router};
 pub use crate::schedule::*;
+pub use crate::session::Session;
 pub use crate::streams::*;
 pub use crate::websocket::*;
 
@@ -51,6 +57,7 @@ mod abort;
 mod cache;
 mod cf;
 mod context;
+mod cookie;
 mod cors;
 mod date;
 mod delay;
@@ -65,13 +72,21 @@ mod headers;
 mod http;
 #[cfg(feature = "queue")]
 mod queue;
+#[cfg(feature = "queue")]
+mod queue_router;
 mod r2;
 mod request;
 mod request_init;
 mod response;
 mod router;
 mod schedule;
+mod session;
+pub mod sse;
 mod streams;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+#[cfg(feature = "tower")]
+pub mod tower;
 mod websocket;
 
 pub type Result<T> = StdResult<T, error::Error>;
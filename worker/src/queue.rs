@@ -1,45 +1,70 @@
-use std::marker::PhantomData;
+use std::{convert::TryFrom, marker::PhantomData};
 
 use crate::{env::EnvBinding, Date, Error, Result};
 use js_sys::Array;
 use serde::{de::DeserializeOwned, Serialize};
 use wasm_bindgen::{prelude::*, JsCast};
 use wasm_bindgen_futures::JsFuture;
-use worker_sys::{MessageBatch as MessageBatchSys, Queue as EdgeQueue};
-
-static BODY_KEY_STR: &str = "body";
-static ID_KEY_STR: &str = "id";
-static TIMESTAMP_KEY_STR: &str = "timestamp";
+use worker_sys::{Message as MessageSys, MessageBatch as MessageBatchSys, Queue as EdgeQueue};
 
 pub struct MessageBatch<T> {
     inner: MessageBatchSys,
     messages: Array,
     data: PhantomData<T>,
-    timestamp_key: JsValue,
-    body_key: JsValue,
-    id_key: JsValue,
 }
 
 impl<T> MessageBatch<T> {
     pub fn new(message_batch_sys: MessageBatchSys) -> Self {
-        let timestamp_key = JsValue::from_str(TIMESTAMP_KEY_STR);
-        let body_key = JsValue::from_str(BODY_KEY_STR);
-        let id_key = JsValue::from_str(ID_KEY_STR);
         Self {
             messages: message_batch_sys.messages(),
             inner: message_batch_sys,
             data: PhantomData,
-            timestamp_key,
-            body_key,
-            id_key,
         }
     }
 }
 
+impl<T> From<MessageBatchSys> for MessageBatch<T> {
+    fn from(message_batch_sys: MessageBatchSys) -> Self {
+        Self::new(message_batch_sys)
+    }
+}
+
 pub struct Message<T> {
     pub body: T,
     pub timestamp: Date,
     pub id: String,
+    /// The number of times delivery of this message has been attempted, including this one.
+    pub attempts: u32,
+    inner: MessageSys,
+}
+
+impl<T> Message<T> {
+    /// Marks this message as successfully delivered, so it will not be redelivered even if the
+    /// rest of the batch is retried.
+    pub fn ack(&self) {
+        self.inner.ack();
+    }
+
+    /// Marks this message to be retried in a later batch.
+    pub fn retry(&self) {
+        self.inner.retry();
+    }
+
+    /// Marks this message to be retried in a later batch, using the given [`QueueRetryOptions`].
+    pub fn retry_with_options(&self, options: &QueueRetryOptions) -> Result<()> {
+        self.inner
+            .retry_with_options(serde_wasm_bindgen::to_value(options)?);
+        Ok(())
+    }
+}
+
+/// Options for retrying a message or a whole batch.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueRetryOptions {
+    /// The number of seconds to delay the redelivery of the message(s) by.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay_seconds: Option<u32>,
 }
 
 impl<T> MessageBatch<T> {
@@ -53,19 +78,29 @@ impl<T> MessageBatch<T> {
         self.inner.retry_all();
     }
 
+    /// Marks every message to be retried in a later batch, using the given [`QueueRetryOptions`].
+    pub fn retry_all_with_options(&self, options: &QueueRetryOptions) -> Result<()> {
+        self.inner
+            .retry_all_with_options(serde_wasm_bindgen::to_value(options)?);
+        Ok(())
+    }
+
+    /// Marks every message as successfully delivered, regardless of whether the handler returns
+    /// an error.
+    pub fn ack_all(&self) {
+        self.inner.ack_all();
+    }
+
     /// Iterator that deserializes messages in the message batch. Ordering of messages is not guaranteed.
     pub fn iter(&self) -> MessageIter<'_, T>
     where
         T: DeserializeOwned,
     {
-        MessageIter {
+        MessageIter(TypedMessageIter {
             range: 0..self.messages.length(),
             array: &self.messages,
-            timestamp_key: &self.timestamp_key,
-            body_key: &self.body_key,
-            id_key: &self.id_key,
             data: PhantomData,
-        }
+        })
     }
 
     /// An array of messages in the batch. Ordering of messages is not guaranteed.
@@ -77,48 +112,222 @@ impl<T> MessageBatch<T> {
     }
 }
 
-pub struct MessageIter<'a, T> {
+/// Iterator over the messages of a [`MessageBatch`], failing on messages whose body can not be
+/// deserialized.
+pub struct MessageIter<'a, T>(TypedMessageIter<'a, T>);
+
+impl<T> std::iter::Iterator for MessageIter<'_, T>
+where
+    T: DeserializeOwned,
+{
+    type Item = Result<Message<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(Message::try_from)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<T> std::iter::DoubleEndedIterator for MessageIter<'_, T>
+where
+    T: DeserializeOwned,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(Message::try_from)
+    }
+}
+
+impl<'a, T> std::iter::FusedIterator for MessageIter<'a, T> where T: DeserializeOwned {}
+
+impl<'a, T> std::iter::ExactSizeIterator for MessageIter<'a, T> where T: DeserializeOwned {}
+
+/// The consumer counterpart of [`TypedQueue`]. Unlike [`MessageBatch::messages`], a message whose
+/// body fails to deserialize into `T` does not fail the whole batch: the error is surfaced on that
+/// message alone, which can still be acknowledged or retried.
+///
+/// Accept it in place of [`MessageBatch`] in a queue handler:
+/// ```no_run
+/// #[event(queue)]
+/// pub async fn main(batch: TypedMessageBatch<OrderEvent>, env: Env, _ctx: Context) -> Result<()> {
+///     for message in batch.iter() {
+///         match message.body() {
+///             Ok(event) => { /* handle event */ message.ack() }
+///             Err(e) => console_error!("malformed message {}: {}", message.id(), e),
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct TypedMessageBatch<T>(MessageBatch<T>);
+
+impl<T> TypedMessageBatch<T> {
+    pub fn new(message_batch_sys: MessageBatchSys) -> Self {
+        Self(MessageBatch::new(message_batch_sys))
+    }
+
+    /// The name of the Queue that belongs to this batch.
+    pub fn queue(&self) -> String {
+        self.0.queue()
+    }
+
+    /// Marks every message to be retried in the next batch.
+    pub fn retry_all(&self) {
+        self.0.retry_all();
+    }
+
+    /// Marks every message to be retried in a later batch, using the given [`QueueRetryOptions`].
+    pub fn retry_all_with_options(&self, options: &QueueRetryOptions) -> Result<()> {
+        self.0.retry_all_with_options(options)
+    }
+
+    /// Marks every message as successfully delivered, regardless of whether the handler returns
+    /// an error.
+    pub fn ack_all(&self) {
+        self.0.ack_all();
+    }
+
+    /// Iterator that deserializes messages in the message batch. Ordering of messages is not guaranteed.
+    pub fn iter(&self) -> TypedMessageIter<'_, T>
+    where
+        T: DeserializeOwned,
+    {
+        TypedMessageIter {
+            range: 0..self.0.messages.length(),
+            array: &self.0.messages,
+            data: PhantomData,
+        }
+    }
+
+    /// An array of messages in the batch. Ordering of messages is not guaranteed.
+    pub fn messages(&self) -> Vec<TypedMessage<T>>
+    where
+        T: DeserializeOwned,
+    {
+        self.iter().collect()
+    }
+}
+
+impl<T> From<MessageBatchSys> for TypedMessageBatch<T> {
+    fn from(message_batch_sys: MessageBatchSys) -> Self {
+        Self::new(message_batch_sys)
+    }
+}
+
+impl<T> From<MessageBatch<T>> for TypedMessageBatch<T> {
+    fn from(message_batch: MessageBatch<T>) -> Self {
+        Self(message_batch)
+    }
+}
+
+/// A message of a [`TypedMessageBatch`], whose body may have failed to deserialize.
+pub struct TypedMessage<T> {
+    body: Result<T>,
+    inner: MessageSys,
+}
+
+impl<T> TypedMessage<T> {
+    /// The deserialized body of the message, or the error encountered while deserializing it.
+    pub fn body(&self) -> std::result::Result<&T, &Error> {
+        self.body.as_ref()
+    }
+
+    /// Consumes the message, returning its deserialized body.
+    pub fn into_body(self) -> Result<T> {
+        self.body
+    }
+
+    /// The body of the message as it was received from the runtime.
+    pub fn raw_body(&self) -> JsValue {
+        self.inner.body()
+    }
+
+    /// The unique identifier of the message.
+    pub fn id(&self) -> String {
+        self.inner.id()
+    }
+
+    /// The time at which the message was sent.
+    pub fn timestamp(&self) -> Date {
+        Date::from(self.inner.timestamp())
+    }
+
+    /// The number of times delivery of this message has been attempted, including this one.
+    pub fn attempts(&self) -> u32 {
+        self.inner.attempts()
+    }
+
+    /// Marks this message as successfully delivered, so it will not be redelivered even if the
+    /// rest of the batch is retried.
+    pub fn ack(&self) {
+        self.inner.ack();
+    }
+
+    /// Marks this message to be retried in a later batch.
+    pub fn retry(&self) {
+        self.inner.retry();
+    }
+
+    /// Marks this message to be retried in a later batch, using the given [`QueueRetryOptions`].
+    pub fn retry_with_options(&self, options: &QueueRetryOptions) -> Result<()> {
+        self.inner
+            .retry_with_options(serde_wasm_bindgen::to_value(options)?);
+        Ok(())
+    }
+}
+
+impl<T> TryFrom<TypedMessage<T>> for Message<T> {
+    type Error = Error;
+
+    fn try_from(message: TypedMessage<T>) -> Result<Self> {
+        let inner = message.inner;
+        Ok(Message {
+            body: message.body?,
+            id: inner.id(),
+            timestamp: Date::from(inner.timestamp()),
+            attempts: inner.attempts(),
+            inner,
+        })
+    }
+}
+
+/// Iterator over the messages of a [`TypedMessageBatch`], or of a [`MessageBatch`] through
+/// [`MessageIter`].
+pub struct TypedMessageIter<'a, T> {
     range: std::ops::Range<u32>,
     array: &'a Array,
-    timestamp_key: &'a JsValue,
-    body_key: &'a JsValue,
-    id_key: &'a JsValue,
     data: PhantomData<T>,
 }
 
-impl<T> MessageIter<'_, T>
+impl<T> TypedMessageIter<'_, T>
 where
     T: DeserializeOwned,
 {
-    fn parse_message(&self, message: &JsValue) -> Result<Message<T>> {
-        let js_date = js_sys::Date::from(js_sys::Reflect::get(message, self.timestamp_key)?);
-        let id = js_sys::Reflect::get(message, self.id_key)?
-            .as_string()
-            .ok_or(Error::JsError(
-                "Invalid message batch. Failed to get id from message.".to_string(),
-            ))?;
-        let body = serde_wasm_bindgen::from_value(js_sys::Reflect::get(message, self.body_key)?)?;
+    fn parse_message(&self, message: JsValue) -> TypedMessage<T> {
+        let message: MessageSys = message.unchecked_into();
+        let body = serde_wasm_bindgen::from_value(message.body()).map_err(Error::from);
 
-        Ok(Message {
-            id,
+        TypedMessage {
             body,
-            timestamp: Date::from(js_date),
-        })
+            inner: message,
+        }
     }
 }
 
-impl<T> std::iter::Iterator for MessageIter<'_, T>
+impl<T> std::iter::Iterator for TypedMessageIter<'_, T>
 where
     T: DeserializeOwned,
 {
-    type Item = Result<Message<T>>;
+    type Item = TypedMessage<T>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let index = self.range.next()?;
-
         let value = self.array.get(index);
 
-        Some(self.parse_message(&value))
+        Some(self.parse_message(value))
     }
 
     #[inline]
@@ -127,7 +336,7 @@ where
     }
 }
 
-impl<T> std::iter::DoubleEndedIterator for MessageIter<'_, T>
+impl<T> std::iter::DoubleEndedIterator for TypedMessageIter<'_, T>
 where
     T: DeserializeOwned,
 {
@@ -135,13 +344,13 @@ where
         let index = self.range.next_back()?;
         let value = self.array.get(index);
 
-        Some(self.parse_message(&value))
+        Some(self.parse_message(value))
     }
 }
 
-impl<'a, T> std::iter::FusedIterator for MessageIter<'a, T> where T: DeserializeOwned {}
+impl<'a, T> std::iter::FusedIterator for TypedMessageIter<'a, T> where T: DeserializeOwned {}
 
-impl<'a, T> std::iter::ExactSizeIterator for MessageIter<'a, T> where T: DeserializeOwned {}
+impl<'a, T> std::iter::ExactSizeIterator for TypedMessageIter<'a, T> where T: DeserializeOwned {}
 
 pub struct Queue(EdgeQueue);
 
@@ -175,6 +384,48 @@ impl AsRef<JsValue> for Queue {
     }
 }
 
+/// The maximum number of messages the runtime accepts in a single `sendBatch` call.
+const MAX_BATCH_SIZE: usize = 100;
+
+/// The maximum total size of the message bodies the runtime accepts in a single `sendBatch` call.
+const MAX_BATCH_BYTES: usize = 256 * 1024;
+
+/// The format a message body is encoded in when it is written to the Queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueContentType {
+    /// The body is serialized as JSON. Messages will be deserialized as JSON by consumers.
+    Json,
+    /// The body must serialize to a string, which is delivered as-is.
+    Text,
+    /// The body must serialize to bytes (e.g. via `serde_bytes`), which are delivered as-is.
+    Bytes,
+    /// The body is serialized with the V8 structured clone algorithm. This is the runtime default.
+    V8,
+}
+
+/// Options for sending a message, or every message of a batch, to a Queue.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendOptions {
+    /// The encoding of the message body.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<QueueContentType>,
+    /// The number of seconds to delay delivery of the message(s) by.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay_seconds: Option<u32>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SendRequest<'a, T> {
+    body: &'a T,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_type: Option<QueueContentType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delay_seconds: Option<u32>,
+}
+
 impl Queue {
     /// Sends a message to the Queue.
     pub async fn send<T>(&self, message: &T) -> Result<()>
@@ -187,4 +438,146 @@ impl Queue {
         fut.await.map_err(Error::from)?;
         Ok(())
     }
+
+    /// Sends a message to the Queue, using the given [`SendOptions`].
+    pub async fn send_with_options<T>(&self, message: &T, options: &SendOptions) -> Result<()>
+    where
+        T: Serialize,
+    {
+        let js_value = serde_wasm_bindgen::to_value(message)?;
+        let options = serde_wasm_bindgen::to_value(options)?;
+        let fut: JsFuture = self.0.send_with_options(js_value, options).into();
+
+        fut.await.map_err(Error::from)?;
+        Ok(())
+    }
+
+    /// Sends every message of the iterator to the Queue. Messages are sent in as few `sendBatch`
+    /// calls as the platform's batch limits allow.
+    ///
+    /// Sending is not atomic across those calls: if one of them fails, the messages of the
+    /// previous calls have already been sent.
+    pub async fn send_batch<T, I>(&self, messages: I) -> Result<()>
+    where
+        T: Serialize,
+        I: IntoIterator<Item = T>,
+    {
+        self.send_batch_with_options(messages, &SendOptions::default())
+            .await
+    }
+
+    /// Sends every message of the iterator to the Queue, applying the given [`SendOptions`] to
+    /// each of them. Messages are sent in as few `sendBatch` calls as the platform's batch limits
+    /// allow, which cap both the number of messages and their total size.
+    ///
+    /// The size of a message is estimated from its JSON encoding, which may differ from how the
+    /// runtime encodes it. Messages that cannot be encoded as JSON are sent in a call of their own.
+    ///
+    /// Sending is not atomic across those calls: if one of them fails, the messages of the
+    /// previous calls have already been sent.
+    pub async fn send_batch_with_options<T, I>(
+        &self,
+        messages: I,
+        options: &SendOptions,
+    ) -> Result<()>
+    where
+        T: Serialize,
+        I: IntoIterator<Item = T>,
+    {
+        let messages: Vec<T> = messages.into_iter().collect();
+
+        let mut chunk = Vec::new();
+        let mut chunk_bytes = 0;
+        for body in &messages {
+            let size = serde_json::to_vec(body).map_or(MAX_BATCH_BYTES, |json| json.len());
+            if !chunk.is_empty()
+                && (chunk.len() == MAX_BATCH_SIZE || chunk_bytes + size > MAX_BATCH_BYTES)
+            {
+                self.send_requests(&chunk).await?;
+                chunk.clear();
+                chunk_bytes = 0;
+            }
+
+            chunk.push(SendRequest {
+                body,
+                content_type: options.content_type,
+                delay_seconds: options.delay_seconds,
+            });
+            chunk_bytes += size;
+        }
+
+        if !chunk.is_empty() {
+            self.send_requests(&chunk).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn send_requests<T: Serialize>(&self, requests: &[SendRequest<'_, T>]) -> Result<()> {
+        let js_value = serde_wasm_bindgen::to_value(requests)?;
+        let fut: JsFuture = self.0.send_batch(js_value).into();
+
+        fut.await.map_err(Error::from)?;
+        Ok(())
+    }
+}
+
+/// A Queue binding that only accepts messages of type `T`. Pair it with a [`TypedMessageBatch`]
+/// of the same `T` in the consumer so both sides share one message schema.
+///
+/// ```no_run
+/// let orders = env.typed_queue::<OrderEvent>("ORDERS")?;
+/// orders.send(&OrderEvent { id: 1 }).await?;
+/// ```
+pub struct TypedQueue<T> {
+    inner: Queue,
+    data: PhantomData<T>,
+}
+
+impl<T> TypedQueue<T>
+where
+    T: Serialize,
+{
+    /// Sends a message to the Queue.
+    pub async fn send(&self, message: &T) -> Result<()> {
+        self.inner.send(message).await
+    }
+
+    /// Sends a message to the Queue, using the given [`SendOptions`].
+    pub async fn send_with_options(&self, message: &T, options: &SendOptions) -> Result<()> {
+        self.inner.send_with_options(message, options).await
+    }
+
+    /// Sends every message of the iterator to the Queue. Messages are sent in as few `sendBatch`
+    /// calls as the platform's batch limits allow, and sending is not atomic across those calls.
+    pub async fn send_batch<I>(&self, messages: I) -> Result<()>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.inner.send_batch(messages).await
+    }
+
+    /// Sends every message of the iterator to the Queue, applying the given [`SendOptions`] to
+    /// each of them. See [`Queue::send_batch_with_options`].
+    pub async fn send_batch_with_options<I>(&self, messages: I, options: &SendOptions) -> Result<()>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.inner.send_batch_with_options(messages, options).await
+    }
+}
+
+impl<T> From<Queue> for TypedQueue<T> {
+    fn from(queue: Queue) -> Self {
+        Self {
+            inner: queue,
+            data: PhantomData,
+        }
+    }
+}
+
+impl<T> AsRef<Queue> for TypedQueue<T> {
+    fn as_ref(&self) -> &Queue {
+        &self.inner
+    }
 }
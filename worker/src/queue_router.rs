@@ -0,0 +1,117 @@
+use std::{collections::HashMap, future::Future, rc::Rc};
+
+use futures_util::future::{self, LocalBoxFuture};
+use worker_sys::MessageBatch as MessageBatchSys;
+
+use crate::{context::Context, env::Env, Error, Result};
+
+type QueueHandlerFn<'a, D> =
+    Rc<dyn 'a + Fn(MessageBatchSys, QueueContext<D>) -> LocalBoxFuture<'a, Result<()>>>;
+
+/// A router dispatching message batches to a handler registered for the name of the Queue they
+/// were delivered from, allowing a single Worker to consume several Queues, each with its own
+/// message type.
+///
+/// Handlers may accept any batch type that can be built from the raw batch, such as
+/// [`MessageBatch<T>`](crate::MessageBatch) or [`TypedMessageBatch<T>`](crate::TypedMessageBatch).
+/// ```no_run
+/// #[event(queue)]
+/// pub async fn main(batch: worker_sys::MessageBatch, env: Env, ctx: Context) -> Result<()> {
+///     QueueRouter::new()
+///         .on_async("orders", |batch: MessageBatch<OrderEvent>, _ctx| async move { Ok(()) })
+///         .on_async("emails", |batch: TypedMessageBatch<Email>, _ctx| async move { Ok(()) })
+///         .run(batch, env, ctx)
+///         .await
+/// }
+/// ```
+pub struct QueueRouter<'a, D> {
+    handlers: HashMap<String, QueueHandlerFn<'a, D>>,
+    data: D,
+}
+
+/// Container for the data shared by a `QueueRouter`, and the environment bindings and context
+/// of the queue event being handled.
+pub struct QueueContext<D> {
+    pub data: D,
+    pub env: Env,
+    pub ctx: Context,
+}
+
+impl<'a> QueueRouter<'a, ()> {
+    /// Construct a new `QueueRouter`. Or, call `QueueRouter::with_data(D)` to add arbitrary data
+    /// that will be available to your various handlers.
+    pub fn new() -> Self {
+        Self::with_data(())
+    }
+}
+
+impl<'a, D: 'a> QueueRouter<'a, D> {
+    /// Construct a new `QueueRouter` with arbitrary data that will be available to your various
+    /// handlers.
+    pub fn with_data(data: D) -> Self {
+        Self {
+            handlers: HashMap::new(),
+            data,
+        }
+    }
+
+    /// Register a handler for message batches delivered from the Queue named `queue`.
+    pub fn on<B>(mut self, queue: &str, func: fn(B, QueueContext<D>) -> Result<()>) -> Self
+    where
+        B: From<MessageBatchSys> + 'a,
+    {
+        self.add_handler(
+            queue,
+            Rc::new(move |batch, ctx| Box::pin(future::ready(func(B::from(batch), ctx)))),
+        );
+        self
+    }
+
+    /// Register a handler for message batches delivered from the Queue named `queue`. Enables the
+    /// use of `async/await` syntax in the callback.
+    pub fn on_async<B, T>(mut self, queue: &str, func: fn(B, QueueContext<D>) -> T) -> Self
+    where
+        B: From<MessageBatchSys> + 'a,
+        T: Future<Output = Result<()>> + 'a,
+    {
+        self.add_handler(
+            queue,
+            Rc::new(move |batch, ctx| Box::pin(func(B::from(batch), ctx))),
+        );
+        self
+    }
+
+    fn add_handler(&mut self, queue: &str, func: QueueHandlerFn<'a, D>) {
+        if self.handlers.insert(queue.to_string(), func).is_some() {
+            panic!("a handler is already registered for the {} queue", queue);
+        }
+    }
+
+    /// Dispatch the message batch to the handler registered for its Queue and return a `Future`.
+    /// Fails if no handler is registered for the Queue.
+    pub async fn run(self, batch: MessageBatchSys, env: Env, ctx: Context) -> Result<()> {
+        let queue = batch.queue();
+        let handler = self.handlers.get(&queue).ok_or_else(|| {
+            Error::RustError(format!("no handler registered for queue `{queue}`"))
+        })?;
+
+        let ctx = QueueContext {
+            data: self.data,
+            env,
+            ctx,
+        };
+        (handler)(batch, ctx).await
+    }
+}
+
+#[test]
+#[should_panic(expected = "a handler is already registered for the orders queue")]
+fn duplicate_handlers_panic() {
+    fn handler(_: crate::MessageBatch<()>, _: QueueContext<()>) -> Result<()> {
+        Ok(())
+    }
+
+    QueueRouter::new()
+        .on("orders", handler)
+        .on("orders", handler);
+}
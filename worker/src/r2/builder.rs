@@ -1,6 +1,7 @@
 use std::{collections::HashMap, convert::TryFrom};
 
-use js_sys::{Array, Date as JsDate, JsString, Object as JsObject, Uint8Array};
+use futures_util::{stream, Stream, StreamExt};
+use js_sys::{Array, ArrayBuffer, Date as JsDate, JsString, Object as JsObject, Uint8Array};
 use wasm_bindgen::{JsCast, JsValue};
 use wasm_bindgen_futures::JsFuture;
 use worker_sys::{
@@ -67,6 +68,9 @@ impl<'bucket> GetOptionsBuilder<'bucket> {
 /// You can pass an [Conditional] object to [GetOptionsBuilder]. If the condition check fails,
 /// the body will not be returned. This will make [get](crate::r2::Bucket::get) have lower latency.
 ///
+/// It can also be passed to [PutOptionsBuilder], in which case the object will only be stored if
+/// the condition check succeeds.
+///
 /// For more information about conditional requests, refer to [RFC 7232](https://datatracker.ietf.org/doc/html/rfc7232).
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Conditional {
@@ -93,34 +97,36 @@ impl From<Conditional> for JsObject {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Range {
-    OffsetWithLength { offset: u32, length: u32 },
-    OffsetWithOptionalLength { offset: u32, length: Option<u32> },
-    OptionalOffsetWithLength { offset: Option<u32>, length: u32 },
-    Suffix { suffix: u32 },
+    OffsetWithLength { offset: u64, length: u64 },
+    OffsetWithOptionalLength { offset: u64, length: Option<u64> },
+    OptionalOffsetWithLength { offset: Option<u64>, length: u64 },
+    Suffix { suffix: u64 },
 }
 
+// JavaScript numbers are doubles, which represent every integer up to 2^53 exactly; far beyond the
+// largest object R2 can store.
 impl From<Range> for JsObject {
     fn from(val: Range) -> Self {
         match val {
             Range::OffsetWithLength { offset, length } => js_object! {
-                "offset" => Some(offset),
-                "length" => Some(length),
+                "offset" => Some(offset as f64),
+                "length" => Some(length as f64),
                 "suffix" => JsValue::UNDEFINED,
             },
             Range::OffsetWithOptionalLength { offset, length } => js_object! {
-                "offset" => Some(offset),
-                "length" => length,
+                "offset" => Some(offset as f64),
+                "length" => length.map(|length| length as f64),
                 "suffix" => JsValue::UNDEFINED,
             },
             Range::OptionalOffsetWithLength { offset, length } => js_object! {
-                "offset" => offset,
-                "length" => Some(length),
+                "offset" => offset.map(|offset| offset as f64),
+                "length" => Some(length as f64),
                 "suffix" => JsValue::UNDEFINED,
             },
             Range::Suffix { suffix } => js_object! {
                 "offset" => JsValue::UNDEFINED,
                 "length" => JsValue::UNDEFINED,
-                "suffix" => Some(suffix),
+                "suffix" => Some(suffix as f64),
             },
         }
     }
@@ -130,7 +136,11 @@ impl TryFrom<R2RangeSys> for Range {
     type Error = Error;
 
     fn try_from(val: R2RangeSys) -> Result<Self> {
-        Ok(match (val.offset, val.length, val.suffix) {
+        let offset = val.offset().map(|offset| offset as u64);
+        let length = val.length().map(|length| length as u64);
+        let suffix = val.suffix().map(|suffix| suffix as u64);
+
+        Ok(match (offset, length, suffix) {
             (Some(offset), Some(length), None) => Self::OffsetWithLength { offset, length },
             (Some(offset), None, None) => Self::OffsetWithOptionalLength {
                 offset,
@@ -146,6 +156,34 @@ impl TryFrom<R2RangeSys> for Range {
     }
 }
 
+/// The storage class of an [Object]. Refer to [Storage classes](https://developers.cloudflare.com/r2/buckets/storage-classes/).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageClass {
+    Standard,
+    InfrequentAccess,
+}
+
+impl StorageClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Standard => "Standard",
+            Self::InfrequentAccess => "InfrequentAccess",
+        }
+    }
+}
+
+impl TryFrom<String> for StorageClass {
+    type Error = Error;
+
+    fn try_from(val: String) -> Result<Self> {
+        Ok(match val.as_str() {
+            "Standard" => Self::Standard,
+            "InfrequentAccess" => Self::InfrequentAccess,
+            _ => return Err(Error::JsError(format!("unknown storage class `{val}`"))),
+        })
+    }
+}
+
 /// Options for configuring the [put](crate::r2::Bucket::put) operation.
 pub struct PutOptionsBuilder<'bucket> {
     pub(crate) edge_bucket: &'bucket EdgeR2Bucket,
@@ -153,7 +191,9 @@ pub struct PutOptionsBuilder<'bucket> {
     pub(crate) value: Data,
     pub(crate) http_metadata: Option<HttpMetadata>,
     pub(crate) custom_metadata: Option<HashMap<String, String>>,
-    pub(crate) md5: Option<Vec<u8>>,
+    pub(crate) checksum: Option<Checksum>,
+    pub(crate) only_if: Option<Conditional>,
+    pub(crate) storage_class: Option<StorageClass>,
 }
 
 impl<'bucket> PutOptionsBuilder<'bucket> {
@@ -169,51 +209,141 @@ impl<'bucket> PutOptionsBuilder<'bucket> {
         self
     }
 
-    /// A md5 hash to use to check the recieved object’s integrity.
+    /// A md5 hash to use to check the received object’s integrity. R2 accepts a single
+    /// checksum per put, so this replaces any checksum set previously.
     pub fn md5(mut self, bytes: impl Into<Vec<u8>>) -> Self {
-        self.md5 = Some(bytes.into());
+        self.checksum = Some(Checksum::Md5(bytes.into()));
+        self
+    }
+
+    /// A SHA-1 hash to use to check the received object’s integrity. R2 accepts a single
+    /// checksum per put, so this replaces any checksum set previously.
+    pub fn sha1(mut self, bytes: impl Into<Vec<u8>>) -> Self {
+        self.checksum = Some(Checksum::Sha1(bytes.into()));
         self
     }
 
-    /// Executes the PUT operation on the R2 bucket.
+    /// A SHA-256 hash to use to check the received object’s integrity. R2 accepts a single
+    /// checksum per put, so this replaces any checksum set previously.
+    pub fn sha256(mut self, bytes: impl Into<Vec<u8>>) -> Self {
+        self.checksum = Some(Checksum::Sha256(bytes.into()));
+        self
+    }
+
+    /// A SHA-384 hash to use to check the received object’s integrity. R2 accepts a single
+    /// checksum per put, so this replaces any checksum set previously.
+    pub fn sha384(mut self, bytes: impl Into<Vec<u8>>) -> Self {
+        self.checksum = Some(Checksum::Sha384(bytes.into()));
+        self
+    }
+
+    /// A SHA-512 hash to use to check the received object’s integrity. R2 accepts a single
+    /// checksum per put, so this replaces any checksum set previously.
+    pub fn sha512(mut self, bytes: impl Into<Vec<u8>>) -> Self {
+        self.checksum = Some(Checksum::Sha512(bytes.into()));
+        self
+    }
+
+    /// Specifies that the object should only be stored given satisfaction of certain conditions
+    /// in the [Conditional]. If the condition check fails, the object is not stored,
+    /// [execute](Self::execute) returns an error and [execute_conditional](Self::execute_conditional)
+    /// returns `None`. Refer to [Conditional operations](https://developers.cloudflare.com/r2/runtime-apis/#conditional-operations).
+    pub fn only_if(mut self, only_if: Conditional) -> Self {
+        self.only_if = Some(only_if);
+        self
+    }
+
+    /// The storage class the object should be stored in. Defaults to the bucket's default storage
+    /// class. Refer to [StorageClass].
+    pub fn storage_class(mut self, storage_class: StorageClass) -> Self {
+        self.storage_class = Some(storage_class);
+        self
+    }
+
+    /// Executes the PUT operation on the R2 bucket. Fails if the object was not stored because the
+    /// condition set with [only_if](Self::only_if) was not satisfied.
     pub async fn execute(self) -> Result<Object> {
+        self.execute_conditional()
+            .await?
+            .ok_or_else(|| Error::RustError("put precondition failed".into()))
+    }
+
+    /// Executes the PUT operation on the R2 bucket. Returns `None` if the object was not stored
+    /// because the condition set with [only_if](Self::only_if) was not satisfied.
+    pub async fn execute_conditional(self) -> Result<Option<Object>> {
         let value: JsValue = self.value.into();
         let name: String = self.key;
 
-        let put_promise = self.edge_bucket.put(
-            name,
-            value,
-            js_object! {
-                "httpMetadata" => self.http_metadata.map(JsObject::from),
-                "customMetadata" => match self.custom_metadata {
-                    Some(metadata) => {
-                        let obj = JsObject::new();
-                        for (k, v) in metadata.into_iter() {
-                            js_sys::Reflect::set(&obj, &JsString::from(k), &JsString::from(v))?;
-                        }
-                        obj.into()
+        let options = js_object! {
+            "httpMetadata" => self.http_metadata.map(JsObject::from),
+            "customMetadata" => match self.custom_metadata {
+                Some(metadata) => {
+                    let obj = JsObject::new();
+                    for (k, v) in metadata.into_iter() {
+                        js_sys::Reflect::set(&obj, &JsString::from(k), &JsString::from(v))?;
                     }
-                    None => JsValue::UNDEFINED,
-                },
-                "md5" => self.md5.map(|bytes| {
-                    let arr = Uint8Array::new_with_length(bytes.len() as _);
-                    arr.copy_from(&bytes);
-                    arr.buffer()
-                })
-            }
-            .into(),
-        );
-        let res: EdgeR2Object = JsFuture::from(put_promise).await?.into();
+                    obj.into()
+                }
+                None => JsValue::UNDEFINED,
+            },
+            "onlyIf" => self.only_if.map(JsObject::from),
+            "storageClass" => self.storage_class.map(|class| class.as_str()),
+        };
+        if let Some(checksum) = self.checksum {
+            let (algorithm, bytes) = checksum.into_parts();
+            js_sys::Reflect::set(
+                &options,
+                &JsString::from(algorithm),
+                &to_array_buffer(bytes),
+            )?;
+        }
+
+        let put_promise = self.edge_bucket.put(name, value, options.into());
+
+        let value = JsFuture::from(put_promise).await?;
+
+        if value.is_null() {
+            return Ok(None);
+        }
+
+        let res: EdgeR2Object = value.into();
         let inner = if JsString::from("bodyUsed").js_in(&res) {
             ObjectInner::Body(res.unchecked_into())
         } else {
             ObjectInner::NoBody(res)
         };
 
-        Ok(Object { inner })
+        Ok(Some(Object { inner }))
+    }
+}
+
+/// The checksum of a [PutOptionsBuilder], keyed by its algorithm.
+pub(crate) enum Checksum {
+    Md5(Vec<u8>),
+    Sha1(Vec<u8>),
+    Sha256(Vec<u8>),
+    Sha384(Vec<u8>),
+    Sha512(Vec<u8>),
+}
+
+impl Checksum {
+    fn into_parts(self) -> (&'static str, Vec<u8>) {
+        match self {
+            Self::Md5(bytes) => ("md5", bytes),
+            Self::Sha1(bytes) => ("sha1", bytes),
+            Self::Sha256(bytes) => ("sha256", bytes),
+            Self::Sha384(bytes) => ("sha384", bytes),
+            Self::Sha512(bytes) => ("sha512", bytes),
+        }
     }
 }
 
+fn to_array_buffer(bytes: Vec<u8>) -> ArrayBuffer {
+    let arr = Uint8Array::new_with_length(bytes.len() as _);
+    arr.copy_from(&bytes);
+    arr.buffer()
+}
+
 /// Options for configuring the [create_multipart_upload](crate::r2::Bucket::create_multipart_upload) operation.
 pub struct CreateMultipartUploadOptionsBuilder<'bucket> {
     pub(crate) edge_bucket: &'bucket EdgeR2Bucket,
@@ -368,14 +498,66 @@ impl<'bucket> ListOptionsBuilder<'bucket> {
 
     /// Executes the LIST operation on the R2 bucket.
     pub async fn execute(self) -> Result<Objects> {
+        self.fetch().await
+    }
+
+    /// Returns a [Stream] of every [Object] matching the options, issuing as many LIST operations
+    /// as needed by following the cursor of each truncated page. The stream ends after yielding
+    /// the first error.
+    ///
+    /// ```no_run
+    /// let mut objects = bucket.list().prefix("logs/").stream();
+    /// while let Some(object) = objects.next().await {
+    ///     let object = object?;
+    /// }
+    /// ```
+    pub fn stream(self) -> impl Stream<Item = Result<Object>> + 'bucket {
+        self.pages().flat_map(|page| {
+            stream::iter(match page {
+                Ok(page) => page.objects().into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(e)],
+            })
+        })
+    }
+
+    /// Like [stream](Self::stream), but yields the [delimited prefixes](Objects::delimited_prefixes)
+    /// of every page instead of its objects. Only useful with a [delimiter](Self::delimiter) set.
+    pub fn stream_delimited_prefixes(self) -> impl Stream<Item = Result<String>> + 'bucket {
+        self.pages().flat_map(|page| {
+            stream::iter(match page {
+                Ok(page) => page.delimited_prefixes().into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(e)],
+            })
+        })
+    }
+
+    fn pages(self) -> impl Stream<Item = Result<Objects>> + 'bucket {
+        stream::unfold(Some(self), |options| async move {
+            let mut options = options?;
+            match options.fetch().await {
+                Ok(page) => {
+                    let cursor = page.cursor().filter(|_| page.truncated());
+                    let next = cursor.map(|cursor| {
+                        options.cursor = Some(cursor);
+                        options
+                    });
+                    Some((Ok(page), next))
+                }
+                Err(e) => Some((Err(e), None)),
+            }
+        })
+    }
+
+    async fn fetch(&self) -> Result<Objects> {
         let list_promise = self.edge_bucket.list(
             js_object! {
                 "limit" => self.limit,
-                "prefix" => self.prefix,
-                "cursor" => self.cursor,
-                "delimiter" => self.delimiter,
+                "prefix" => self.prefix.clone(),
+                "cursor" => self.cursor.clone(),
+                "delimiter" => self.delimiter.clone(),
                 "include" => self
                     .include
+                    .as_ref()
                     .map(|include| {
                         let arr = Array::new();
                         for include in include {
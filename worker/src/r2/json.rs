@@ -0,0 +1,73 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::Result;
+
+use super::{Bucket, HttpMetadata, PutOptionsBuilder};
+
+/// A value deserialized from an [Object](super::Object), along with the etag of the object it was
+/// read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Versioned<T> {
+    pub value: T,
+    /// The etag of the object the value was read from. Pass it to
+    /// [Conditional::etag_matches](super::Conditional::etag_matches) when writing the value back
+    /// to only overwrite the object if it hasn't changed since.
+    pub etag: String,
+}
+
+impl Bucket {
+    /// Retrieves the object stored under `key` and deserializes its body as JSON, if the key
+    /// exists.
+    ///
+    /// Together with [put_json](Self::put_json) and [only_if](PutOptionsBuilder::only_if), this
+    /// allows updating a JSON document with compare-and-swap semantics:
+    ///
+    /// ```no_run
+    /// let Versioned { mut value, etag } = bucket.get_json::<Config>("config.json").await?.unwrap();
+    /// value.version += 1;
+    /// let swapped = bucket
+    ///     .put_json("config.json", &value)?
+    ///     .only_if(Conditional {
+    ///         etag_matches: Some(etag),
+    ///         ..Default::default()
+    ///     })
+    ///     .execute_conditional()
+    ///     .await?
+    ///     .is_some();
+    /// ```
+    pub async fn get_json<T: DeserializeOwned>(
+        &self,
+        key: impl Into<String>,
+    ) -> Result<Option<Versioned<T>>> {
+        let object = match self.get(key).execute().await? {
+            Some(object) => object,
+            None => return Ok(None),
+        };
+
+        // Empty objects are returned without a body by some runtimes.
+        let bytes = match object.body() {
+            Some(body) => body.bytes().await?,
+            None => Vec::new(),
+        };
+
+        Ok(Some(Versioned {
+            value: serde_json::from_slice(&bytes)?,
+            etag: object.etag(),
+        }))
+    }
+
+    /// Serializes `value` as JSON to be stored under `key`, with its `Content-Type` set to
+    /// `application/json`. Further options can be set on the returned builder before executing it;
+    /// setting [http_metadata](PutOptionsBuilder::http_metadata) replaces the `Content-Type`.
+    pub fn put_json<T: Serialize>(
+        &self,
+        key: impl Into<String>,
+        value: &T,
+    ) -> Result<PutOptionsBuilder<'_>> {
+        let value = serde_json::to_vec(value)?;
+        Ok(self.put(key, value).http_metadata(HttpMetadata {
+            content_type: Some("application/json".into()),
+            ..Default::default()
+        }))
+    }
+}
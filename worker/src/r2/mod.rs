@@ -1,20 +1,38 @@
-use std::{collections::HashMap, convert::TryInto};
+use std::{
+    collections::HashMap,
+    convert::{TryFrom, TryInto},
+};
 
 pub use builder::*;
+pub use json::Versioned;
+pub use store::*;
+pub use upload_handler::MultipartUploadHandler;
+pub use writer::R2Writer;
 
-use js_sys::{JsString, Reflect, Uint8Array};
+use js_sys::{JsString, Object as JsObject, Reflect, Uint8Array};
 use wasm_bindgen::{JsCast, JsValue};
 use wasm_bindgen_futures::JsFuture;
 use worker_sys::{
     FixedLengthStream as EdgeFixedLengthStream, R2Bucket as EdgeR2Bucket,
-    R2MultipartUpload as EdgeR2MultipartUpload, R2Object as EdgeR2Object,
-    R2ObjectBody as EdgeR2ObjectBody, R2Objects as EdgeR2Objects,
+    R2Checksums as R2ChecksumsSys, R2MultipartUpload as EdgeR2MultipartUpload,
+    R2Object as EdgeR2Object, R2ObjectBody as EdgeR2ObjectBody, R2Objects as EdgeR2Objects,
     R2UploadedPart as EdgeR2UploadedPart,
 };
 
-use crate::{env::EnvBinding, ByteStream, Date, Error, FixedLengthStream, Headers, Result};
+use crate::{
+    env::EnvBinding, ByteStream, ByteStreamReader, Date, Error, FixedLengthStream, Headers,
+    Response, ResponseBody, Result,
+};
 
 mod builder;
+mod json;
+mod serve;
+mod store;
+mod upload_handler;
+mod writer;
+
+/// The maximum number of keys the runtime accepts in a single delete operation.
+const MAX_DELETE_KEYS: usize = 1000;
 
 /// An instance of the R2 bucket binding.
 pub struct Bucket {
@@ -60,7 +78,9 @@ impl Bucket {
             value: value.into(),
             http_metadata: None,
             custom_metadata: None,
-            md5: None,
+            checksum: None,
+            only_if: None,
+            storage_class: None,
         }
     }
 
@@ -75,6 +95,28 @@ impl Bucket {
         Ok(())
     }
 
+    /// Deletes the values and metadata under each of the given keys. Keys are deleted in batches
+    /// of up to 1000, the most the runtime accepts in a single operation.
+    ///
+    /// R2 deletes are strongly consistent. Once the future resolves, all subsequent read
+    /// operations will no longer see these key value pairs globally.
+    pub async fn delete_many<K>(&self, keys: impl IntoIterator<Item = K>) -> Result<()>
+    where
+        K: Into<String>,
+    {
+        let keys: Vec<JsValue> = keys
+            .into_iter()
+            .map(|key| JsString::from(key.into()).into())
+            .collect();
+
+        for chunk in keys.chunks(MAX_DELETE_KEYS) {
+            let delete_promise = self.inner.delete_multiple(chunk.to_vec());
+            JsFuture::from(delete_promise).await?;
+        }
+
+        Ok(())
+    }
+
     /// Returns an [Objects] containing a list of [Objects]s contained within the bucket. By
     /// default, returns the first 1000 entries.
     pub fn list(&self) -> ListOptionsBuilder {
@@ -176,11 +218,13 @@ impl Object {
         }
     }
 
-    pub fn size(&self) -> u32 {
-        match &self.inner {
+    pub fn size(&self) -> u64 {
+        let size = match &self.inner {
             ObjectInner::NoBody(inner) => inner.size(),
             ObjectInner::Body(inner) => inner.size(),
-        }
+        };
+
+        size as u64
     }
 
     pub fn etag(&self) -> String {
@@ -231,11 +275,31 @@ impl Object {
         Ok(map)
     }
 
+    /// The storage class the object is stored in. Objects stored before storage classes were
+    /// introduced are in the [Standard](StorageClass::Standard) storage class.
+    pub fn storage_class(&self) -> Result<StorageClass> {
+        match &self.inner {
+            ObjectInner::NoBody(inner) => inner.storage_class(),
+            ObjectInner::Body(inner) => inner.storage_class(),
+        }
+        .map_or(Ok(StorageClass::Standard), StorageClass::try_from)
+    }
+
+    /// The hashes of the object's data that were provided when it was uploaded, or computed by R2.
+    pub fn checksums(&self) -> Checksums {
+        match &self.inner {
+            ObjectInner::NoBody(inner) => inner.checksums(),
+            ObjectInner::Body(inner) => inner.checksums(),
+        }
+        .into()
+    }
+
     pub fn range(&self) -> Result<Range> {
         match &self.inner {
             ObjectInner::NoBody(inner) => inner.range(),
             ObjectInner::Body(inner) => inner.range(),
         }
+        .ok_or_else(|| Error::JsError("object has no range".into()))?
         .try_into()
     }
 
@@ -263,6 +327,29 @@ impl Object {
     }
 }
 
+/// Responds with the [Object], streaming its body if it has one. The response carries the
+/// object's [HttpMetadata] along with its `ETag`. Objects without a body, such as those returned by
+/// [head](Bucket::head), produce an empty response.
+///
+/// Use [serve](Bucket::serve) to honor the range and conditional headers of a request.
+impl TryFrom<Object> for Response {
+    type Error = Error;
+
+    fn try_from(object: Object) -> Result<Self> {
+        let headers = Headers::new();
+        // Share the underlying JS object so the metadata is written to `headers`.
+        object.write_http_metadata(Headers(headers.0.clone()))?;
+        headers.0.set("etag", &object.http_etag())?;
+
+        let body = match &object.inner {
+            ObjectInner::Body(body) => ResponseBody::Stream(body.body()),
+            ObjectInner::NoBody(_) => ResponseBody::Empty,
+        };
+
+        Ok(Response::from_body(body)?.with_headers(headers))
+    }
+}
+
 /// The data contained within an [Object].
 pub struct ObjectBody<'body> {
     inner: &'body EdgeR2ObjectBody,
@@ -282,6 +369,12 @@ impl<'body> ObjectBody<'body> {
         })
     }
 
+    /// Reads the data in the [Object] through [AsyncRead](futures_util::AsyncRead) and
+    /// [AsyncBufRead](futures_util::AsyncBufRead). Refer to [ByteStream::into_async_read].
+    pub fn into_async_read(self) -> Result<ByteStreamReader> {
+        Ok(self.stream()?.into_async_read())
+    }
+
     pub async fn bytes(self) -> Result<Vec<u8>> {
         let js_buffer = JsFuture::from(self.inner.array_buffer()).await?;
         let js_buffer = Uint8Array::new(&js_buffer);
@@ -304,6 +397,18 @@ pub struct UploadedPart {
 }
 
 impl UploadedPart {
+    /// Recreates an [UploadedPart] from its part number and etag, for instance to complete an
+    /// upload whose parts were uploaded by earlier requests.
+    pub fn new(part_number: u16, etag: impl Into<String>) -> Self {
+        let obj = js_object! {
+            "partNumber" => part_number,
+            "etag" => etag.into(),
+        };
+        Self {
+            inner: obj.unchecked_into(),
+        }
+    }
+
     pub fn part_number(&self) -> u16 {
         self.inner.part_number()
     }
@@ -318,6 +423,17 @@ pub struct MultipartUpload {
 }
 
 impl MultipartUpload {
+    /// The key of the object this multipart upload is for.
+    pub fn key(&self) -> String {
+        self.inner.key()
+    }
+
+    /// The id of this multipart upload, which can be used to
+    /// [resume](Bucket::resume_multipart_upload) it later on.
+    pub fn upload_id(&self) -> String {
+        self.inner.upload_id()
+    }
+
     /// Uploads a single part with the specified part number to this multipart upload.
     ///
     /// Returns an [UploadedPart] object containing the etag and part number.
@@ -413,6 +529,29 @@ impl Objects {
     }
 }
 
+/// The hashes of an [Object]'s data. Refer to [Object::checksums].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Checksums {
+    pub md5: Option<Vec<u8>>,
+    pub sha1: Option<Vec<u8>>,
+    pub sha256: Option<Vec<u8>>,
+    pub sha384: Option<Vec<u8>>,
+    pub sha512: Option<Vec<u8>>,
+}
+
+impl From<R2ChecksumsSys> for Checksums {
+    fn from(val: R2ChecksumsSys) -> Self {
+        let to_vec = |buffer: js_sys::ArrayBuffer| Uint8Array::new(&buffer).to_vec();
+        Self {
+            md5: val.md5().map(to_vec),
+            sha1: val.sha1().map(to_vec),
+            sha256: val.sha256().map(to_vec),
+            sha384: val.sha384().map(to_vec),
+            sha512: val.sha512().map(to_vec),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub(crate) enum ObjectInner {
     NoBody(EdgeR2Object),
@@ -0,0 +1,354 @@
+use js_sys::{JsString, Object as JsObject};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use worker_sys::R2Object as EdgeR2Object;
+
+use crate::{Date, Headers, Method, Request, Response, ResponseBody, Result};
+
+use super::{js_object, Bucket, Object, ObjectInner, Range};
+
+impl Bucket {
+    /// Serves the [Object] stored under `key` as the response to `req`, honoring its `Range`,
+    /// `If-Range`, `If-Match`, `If-None-Match`, `If-Modified-Since` and `If-Unmodified-Since`
+    /// headers.
+    ///
+    /// The response carries the object's [HttpMetadata](super::HttpMetadata) along with `ETag`,
+    /// `Last-Modified` and `Accept-Ranges` headers, and has one of the following statuses:
+    /// * `200` with the whole object, or `206` with the requested range and a `Content-Range`
+    ///   header. Requests for multiple ranges, or with an unparsable `Range`, get the whole object.
+    /// * `304` or `412` without a body when a precondition fails.
+    /// * `404` when no object is stored under `key`, `405` for methods other than GET and HEAD, and
+    ///   `416` when the requested range starts past the end of the object.
+    pub async fn serve(&self, req: &Request, key: impl Into<String>) -> Result<Response> {
+        let key = key.into();
+        let method = req.method();
+        if method != Method::Get && method != Method::Head {
+            return Response::error("Method Not Allowed", 405);
+        }
+
+        let headers = req.headers();
+        let mut range = headers.get("range")?.and_then(|range| parse_range(&range));
+
+        // Ranges are only honored if the object still has the representation the `If-Range`
+        // validator was taken from, which can only be known ahead of reading it.
+        let head = match (&range, headers.get("if-range")?) {
+            (Some(_), Some(validator)) => match self.head(key.clone()).await? {
+                Some(object) => {
+                    if validator != object.http_etag() && validator != http_date(&object.uploaded())
+                    {
+                        range = None;
+                    }
+                    Some(object)
+                }
+                None => return Response::error("Not Found", 404),
+            },
+            _ => None,
+        };
+
+        let object = if method == Method::Head {
+            match head {
+                Some(object) => Some(object),
+                None => self.head(key.clone()).await?,
+            }
+        } else {
+            let get_promise = self.inner.get(
+                key.clone(),
+                js_object! {
+                    "onlyIf" => headers.0.clone(),
+                    "range" => range.clone().map(JsObject::from),
+                }
+                .into(),
+            );
+
+            match JsFuture::from(get_promise).await {
+                Ok(value) if value.is_null() => None,
+                Ok(value) => {
+                    let res: EdgeR2Object = value.into();
+                    Some(Object {
+                        inner: if JsString::from("bodyUsed").js_in(&res) {
+                            ObjectInner::Body(res.unchecked_into())
+                        } else {
+                            ObjectInner::NoBody(res)
+                        },
+                    })
+                }
+                // R2 rejects ranges starting past the end of the object.
+                Err(e) => match (&range, self.head(key.clone()).await?) {
+                    (Some(range), Some(object)) if !is_satisfiable(range, object.size()) => {
+                        let mut headers = Headers::new();
+                        headers.set("content-range", &format!("bytes */{}", object.size()))?;
+                        return Ok(
+                            Response::error("Range Not Satisfiable", 416)?.with_headers(headers)
+                        );
+                    }
+                    _ => return Err(e.into()),
+                },
+            }
+        };
+
+        let object = match object {
+            Some(object) => object,
+            None => return Response::error("Not Found", 404),
+        };
+
+        let mut response_headers = Headers::new();
+        // Share the underlying JS object so the metadata is written to `response_headers`.
+        object.write_http_metadata(Headers(response_headers.0.clone()))?;
+        response_headers.set("etag", &object.http_etag())?;
+        response_headers.set("last-modified", &http_date(&object.uploaded()))?;
+        response_headers.set("accept-ranges", "bytes")?;
+
+        let failed_precondition =
+            failed_precondition(headers, &object.http_etag(), object.uploaded().as_millis())?;
+
+        if method == Method::Head {
+            if let Some(status) = failed_precondition {
+                return Ok(Response::empty()?
+                    .with_status(status)
+                    .with_headers(response_headers));
+            }
+
+            response_headers.set("content-length", &object.size().to_string())?;
+            return Ok(Response::empty()?.with_headers(response_headers));
+        }
+
+        let body = match &object.inner {
+            ObjectInner::Body(body) => body.body(),
+            // R2 withholds the body when a precondition fails.
+            ObjectInner::NoBody(_) => {
+                return Ok(Response::empty()?
+                    .with_status(failed_precondition.unwrap_or(412))
+                    .with_headers(response_headers));
+            }
+        };
+
+        let mut status = 200;
+        if range.is_some() {
+            if let Ok(applied) = object.range() {
+                let (start, end) = range_bounds(&applied, object.size());
+                response_headers.set(
+                    "content-range",
+                    &format!("bytes {}-{}/{}", start, end, object.size()),
+                )?;
+                status = 206;
+            }
+        }
+
+        Ok(Response::from_body(ResponseBody::Stream(body))?
+            .with_status(status)
+            .with_headers(response_headers))
+    }
+}
+
+/// Formats a date as an HTTP-date, e.g. `Tue, 15 Nov 1994 08:12:31 GMT`.
+fn http_date(date: &Date) -> String {
+    js_sys::Date::from(date.clone()).to_utc_string().into()
+}
+
+/// Parses an HTTP-date into milliseconds since the Unix epoch.
+fn parse_http_date(date: &str) -> Option<u64> {
+    let millis = js_sys::Date::parse(date);
+    if millis.is_nan() || millis < 0.0 {
+        None
+    } else {
+        Some(millis as u64)
+    }
+}
+
+/// Evaluates the request's preconditions against the object, in the order specified by
+/// [RFC 7232](https://datatracker.ietf.org/doc/html/rfc7232#section-6), returning the status to
+/// respond with if one of them fails.
+fn failed_precondition(headers: &Headers, etag: &str, uploaded: u64) -> Result<Option<u16>> {
+    // HTTP-dates have a resolution of one second.
+    let uploaded = uploaded / 1000;
+
+    if let Some(if_match) = headers.get("if-match")? {
+        if !etag_list_matches(&if_match, etag, false) {
+            return Ok(Some(412));
+        }
+    } else if let Some(since) = headers.get("if-unmodified-since")? {
+        if matches!(parse_http_date(&since), Some(since) if uploaded > since / 1000) {
+            return Ok(Some(412));
+        }
+    }
+
+    if let Some(if_none_match) = headers.get("if-none-match")? {
+        if etag_list_matches(&if_none_match, etag, true) {
+            return Ok(Some(304));
+        }
+    } else if let Some(since) = headers.get("if-modified-since")? {
+        if matches!(parse_http_date(&since), Some(since) if uploaded <= since / 1000) {
+            return Ok(Some(304));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Whether the `If-Match`/`If-None-Match` header value matches the given entity tag, using the
+/// weak comparison function if `weak` is set and the strong one otherwise.
+fn etag_list_matches(header: &str, etag: &str, weak: bool) -> bool {
+    if header.trim() == "*" {
+        return true;
+    }
+
+    let strip = |tag: &str| -> Option<String> {
+        match tag.strip_prefix("W/") {
+            Some(_) if !weak => None,
+            Some(tag) => Some(tag.to_string()),
+            None => Some(tag.to_string()),
+        }
+    };
+
+    let etag = match strip(etag.trim()) {
+        Some(etag) => etag,
+        None => return false,
+    };
+
+    header
+        .split(',')
+        .filter_map(|tag| strip(tag.trim()))
+        .any(|tag| tag == etag)
+}
+
+/// Parses a `Range` header holding a single byte range. Returns `None` for anything else, in
+/// which case the header should be ignored.
+fn parse_range(header: &str) -> Option<Range> {
+    let (unit, spec) = header.trim().split_once('=')?;
+    if !unit.trim().eq_ignore_ascii_case("bytes") || spec.contains(',') {
+        return None;
+    }
+
+    let (first, last) = spec.trim().split_once('-')?;
+    let (first, last) = (first.trim(), last.trim());
+
+    match (first.is_empty(), last.is_empty()) {
+        (true, false) => match last.parse().ok()? {
+            0 => None,
+            suffix => Some(Range::Suffix { suffix }),
+        },
+        (false, true) => Some(Range::OffsetWithOptionalLength {
+            offset: first.parse().ok()?,
+            length: None,
+        }),
+        (false, false) => {
+            let offset: u64 = first.parse().ok()?;
+            let last: u64 = last.parse().ok()?;
+            // Ranges whose length doesn't fit in a `u64` are ignored like any other invalid one.
+            let length = last.checked_sub(offset)?.checked_add(1)?;
+            Some(Range::OffsetWithLength { offset, length })
+        }
+        (true, true) => None,
+    }
+}
+
+/// Whether the range overlaps an object of the given size.
+fn is_satisfiable(range: &Range, size: u64) -> bool {
+    match range {
+        Range::OffsetWithLength { offset, .. } | Range::OffsetWithOptionalLength { offset, .. } => {
+            *offset < size
+        }
+        Range::OptionalOffsetWithLength { offset, .. } => offset.unwrap_or(0) < size,
+        Range::Suffix { suffix } => *suffix > 0,
+    }
+}
+
+/// The first and last byte positions selected by a range within an object of the given size.
+fn range_bounds(range: &Range, size: u64) -> (u64, u64) {
+    let last = size.saturating_sub(1);
+    // Ranges running past the end of the object, including ones whose end overflows, stop at its
+    // last byte.
+    let end = |offset: u64, length: u64| match offset.checked_add(length) {
+        Some(end) => end.saturating_sub(1).min(last),
+        None => last,
+    };
+    match range {
+        Range::OffsetWithLength { offset, length } => (*offset, end(*offset, *length)),
+        Range::OffsetWithOptionalLength { offset, length } => match length {
+            Some(length) => (*offset, end(*offset, *length)),
+            None => (*offset, last),
+        },
+        Range::OptionalOffsetWithLength { offset, length } => {
+            let offset = offset.unwrap_or(0);
+            (offset, end(offset, *length))
+        }
+        Range::Suffix { suffix } => (size.saturating_sub(*suffix), last),
+    }
+}
+
+#[test]
+fn parse_range_works() {
+    assert_eq!(
+        parse_range("bytes=0-499"),
+        Some(Range::OffsetWithLength {
+            offset: 0,
+            length: 500
+        })
+    );
+    assert_eq!(
+        parse_range("bytes=4294967296-"),
+        Some(Range::OffsetWithOptionalLength {
+            offset: 4294967296,
+            length: None
+        })
+    );
+    assert_eq!(
+        parse_range("bytes=-500"),
+        Some(Range::Suffix { suffix: 500 })
+    );
+    assert_eq!(parse_range("bytes=-0"), None);
+    assert_eq!(parse_range("bytes=500-499"), None);
+    assert_eq!(parse_range("bytes=0-1,4-5"), None);
+    assert_eq!(parse_range("items=0-1"), None);
+    assert_eq!(parse_range("bytes=a-b"), None);
+}
+
+#[test]
+fn parse_range_ignores_overflowing_ranges() {
+    assert_eq!(parse_range("bytes=0-18446744073709551615"), None);
+}
+
+#[test]
+fn parse_range_rejects_offsets_past_any_object() {
+    let range = parse_range("bytes=18446744073709551615-").unwrap();
+    assert_eq!(
+        range,
+        Range::OffsetWithOptionalLength {
+            offset: u64::MAX,
+            length: None
+        }
+    );
+    assert!(!is_satisfiable(&range, 50));
+}
+
+#[test]
+fn range_bounds_works() {
+    let range = Range::OffsetWithLength {
+        offset: 10,
+        length: 100,
+    };
+    assert_eq!(range_bounds(&range, 50), (10, 49));
+    assert_eq!(range_bounds(&Range::Suffix { suffix: 10 }, 50), (40, 49));
+    assert_eq!(range_bounds(&Range::Suffix { suffix: 100 }, 50), (0, 49));
+    let range = Range::OffsetWithLength {
+        offset: 10,
+        length: u64::MAX,
+    };
+    assert_eq!(range_bounds(&range, 50), (10, 49));
+    assert!(!is_satisfiable(
+        &Range::OffsetWithOptionalLength {
+            offset: 50,
+            length: None
+        },
+        50
+    ));
+}
+
+#[test]
+fn etag_list_matches_works() {
+    assert!(etag_list_matches("*", "\"abc\"", false));
+    assert!(etag_list_matches("\"xyz\", \"abc\"", "\"abc\"", false));
+    assert!(etag_list_matches("W/\"abc\"", "\"abc\"", true));
+    assert!(!etag_list_matches("W/\"abc\"", "\"abc\"", false));
+    assert!(!etag_list_matches("\"xyz\"", "\"abc\"", true));
+}
@@ -0,0 +1,323 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use crate::{ByteStream, Result};
+
+use super::{
+    Bucket, Conditional, Data, HttpMetadata, Include, Object, Range, StorageClass, UploadedPart,
+};
+
+/// The operations of an R2 bucket, abstracted so that code written against this trait can be
+/// run against a [Bucket] in production, and against an in-memory bucket such as
+/// `testing::MemoryBucket` (enabled by the `testing` feature) in native unit tests.
+///
+/// Unlike [Object], the values exchanged through this trait are plain Rust values, which can be
+/// created and inspected outside of a JavaScript runtime.
+///
+/// ```no_run
+/// async fn latest_release(store: &impl BucketStore) -> Result<Option<String>> {
+///     let releases = store
+///         .list(ListObjectsOptions {
+///             prefix: Some("releases/".into()),
+///             ..Default::default()
+///         })
+///         .await?;
+///     Ok(releases.objects.into_iter().map(|object| object.key).max())
+/// }
+/// ```
+#[async_trait(?Send)]
+pub trait BucketStore {
+    /// Retrieves the metadata of the object stored under `key`, if the key exists.
+    async fn head(&self, key: &str) -> Result<Option<ObjectInfo>>;
+
+    /// Retrieves the object stored under `key`, if the key exists. If the precondition in the
+    /// options fails, the object is returned without a body.
+    async fn get(&self, key: &str, options: GetObjectOptions) -> Result<Option<StoredObject>>;
+
+    /// Stores `value` under `key`, returning the metadata of the stored object, or `None` if the
+    /// precondition in the options failed.
+    async fn put(
+        &self,
+        key: &str,
+        value: Data,
+        options: PutObjectOptions,
+    ) -> Result<Option<ObjectInfo>>;
+
+    /// Deletes the object stored under `key`, if any.
+    async fn delete(&self, key: &str) -> Result<()>;
+
+    /// Lists the objects in the bucket, one page at a time.
+    async fn list(&self, options: ListObjectsOptions) -> Result<ObjectList>;
+
+    /// Creates a multipart upload for `key`, returning its upload id.
+    async fn create_multipart_upload(
+        &self,
+        key: &str,
+        options: CreateMultipartUploadOptions,
+    ) -> Result<String>;
+
+    /// Uploads a part of the multipart upload with the given id.
+    async fn upload_part(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_number: u16,
+        value: Data,
+    ) -> Result<PartInfo>;
+
+    /// Completes the multipart upload with the given id from the given parts, returning the
+    /// metadata of the stored object.
+    async fn complete_multipart_upload(
+        &self,
+        key: &str,
+        upload_id: &str,
+        parts: Vec<PartInfo>,
+    ) -> Result<ObjectInfo>;
+
+    /// Aborts the multipart upload with the given id.
+    async fn abort_multipart_upload(&self, key: &str, upload_id: &str) -> Result<()>;
+}
+
+/// Options for [BucketStore::get]. Refer to [GetOptionsBuilder](super::GetOptionsBuilder).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GetObjectOptions {
+    pub only_if: Option<Conditional>,
+    pub range: Option<Range>,
+}
+
+/// Options for [BucketStore::put]. Refer to [PutOptionsBuilder](super::PutOptionsBuilder).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PutObjectOptions {
+    pub http_metadata: Option<HttpMetadata>,
+    pub custom_metadata: Option<HashMap<String, String>>,
+    pub only_if: Option<Conditional>,
+    pub storage_class: Option<StorageClass>,
+}
+
+/// Options for [BucketStore::list]. Refer to [ListOptionsBuilder](super::ListOptionsBuilder).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListObjectsOptions {
+    pub limit: Option<u32>,
+    pub prefix: Option<String>,
+    pub cursor: Option<String>,
+    pub delimiter: Option<String>,
+    pub include: Vec<Include>,
+}
+
+/// Options for [BucketStore::create_multipart_upload]. Refer to
+/// [CreateMultipartUploadOptionsBuilder](super::CreateMultipartUploadOptionsBuilder).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CreateMultipartUploadOptions {
+    pub http_metadata: Option<HttpMetadata>,
+    pub custom_metadata: Option<HashMap<String, String>>,
+}
+
+/// The metadata of a stored object. Refer to [Object].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectInfo {
+    pub key: String,
+    pub version: String,
+    pub size: u64,
+    pub etag: String,
+    pub http_etag: String,
+    /// When the object was uploaded, in milliseconds since the Unix epoch.
+    pub uploaded_millis: u64,
+    pub http_metadata: HttpMetadata,
+    pub custom_metadata: HashMap<String, String>,
+    /// The range of the object that was returned by a ranged [get](BucketStore::get).
+    pub range: Option<Range>,
+    pub storage_class: StorageClass,
+}
+
+impl ObjectInfo {
+    fn from_object(object: &Object) -> Result<Self> {
+        Ok(Self {
+            key: object.key(),
+            version: object.version(),
+            size: object.size(),
+            etag: object.etag(),
+            http_etag: object.http_etag(),
+            uploaded_millis: object.uploaded().as_millis(),
+            http_metadata: object.http_metadata(),
+            custom_metadata: object.custom_metadata()?,
+            range: object.range().ok(),
+            storage_class: object.storage_class()?,
+        })
+    }
+}
+
+/// An object returned by [BucketStore::get].
+pub struct StoredObject {
+    pub info: ObjectInfo,
+    /// The body of the object, or `None` if the precondition of the request failed.
+    pub body: Option<StoredBody>,
+}
+
+/// The body of a [StoredObject].
+pub enum StoredBody {
+    Bytes(Vec<u8>),
+    Stream(ByteStream),
+}
+
+impl StoredBody {
+    /// Reads the whole body.
+    pub async fn bytes(self) -> Result<Vec<u8>> {
+        use futures_util::TryStreamExt;
+
+        match self {
+            Self::Bytes(bytes) => Ok(bytes),
+            Self::Stream(stream) => stream.try_concat().await,
+        }
+    }
+
+    /// Reads the whole body as UTF-8 text.
+    pub async fn text(self) -> Result<String> {
+        String::from_utf8(self.bytes().await?).map_err(|e| e.to_string().into())
+    }
+}
+
+/// A page of objects returned by [BucketStore::list]. Refer to [Objects](super::Objects).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObjectList {
+    pub objects: Vec<ObjectInfo>,
+    pub truncated: bool,
+    pub cursor: Option<String>,
+    pub delimited_prefixes: Vec<String>,
+}
+
+/// A part of a multipart upload. Refer to [UploadedPart].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartInfo {
+    pub part_number: u16,
+    pub etag: String,
+}
+
+#[async_trait(?Send)]
+impl BucketStore for Bucket {
+    async fn head(&self, key: &str) -> Result<Option<ObjectInfo>> {
+        self.head(key)
+            .await?
+            .map(|object| ObjectInfo::from_object(&object))
+            .transpose()
+    }
+
+    async fn get(&self, key: &str, options: GetObjectOptions) -> Result<Option<StoredObject>> {
+        let mut builder = self.get(key);
+        builder.only_if = options.only_if;
+        builder.range = options.range;
+
+        let object = match builder.execute().await? {
+            Some(object) => object,
+            None => return Ok(None),
+        };
+
+        let body = match object.body() {
+            Some(body) => Some(StoredBody::Stream(body.stream()?)),
+            None => None,
+        };
+
+        Ok(Some(StoredObject {
+            info: ObjectInfo::from_object(&object)?,
+            body,
+        }))
+    }
+
+    async fn put(
+        &self,
+        key: &str,
+        value: Data,
+        options: PutObjectOptions,
+    ) -> Result<Option<ObjectInfo>> {
+        let mut builder = self.put(key, value);
+        builder.http_metadata = options.http_metadata;
+        builder.custom_metadata = options.custom_metadata;
+        builder.only_if = options.only_if;
+        builder.storage_class = options.storage_class;
+
+        builder
+            .execute_conditional()
+            .await?
+            .map(|object| ObjectInfo::from_object(&object))
+            .transpose()
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.delete(key).await
+    }
+
+    async fn list(&self, options: ListObjectsOptions) -> Result<ObjectList> {
+        let mut builder = self.list();
+        builder.limit = options.limit;
+        builder.prefix = options.prefix;
+        builder.cursor = options.cursor;
+        builder.delimiter = options.delimiter;
+        if !options.include.is_empty() {
+            builder.include = Some(options.include);
+        }
+
+        let objects = builder.execute().await?;
+        Ok(ObjectList {
+            objects: objects
+                .objects()
+                .iter()
+                .map(ObjectInfo::from_object)
+                .collect::<Result<_>>()?,
+            truncated: objects.truncated(),
+            cursor: objects.cursor(),
+            delimited_prefixes: objects.delimited_prefixes(),
+        })
+    }
+
+    async fn create_multipart_upload(
+        &self,
+        key: &str,
+        options: CreateMultipartUploadOptions,
+    ) -> Result<String> {
+        let mut builder = self.create_multipart_upload(key);
+        builder.http_metadata = options.http_metadata;
+        builder.custom_metadata = options.custom_metadata;
+
+        Ok(builder.execute().await?.upload_id())
+    }
+
+    async fn upload_part(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_number: u16,
+        value: Data,
+    ) -> Result<PartInfo> {
+        let part = self
+            .resume_multipart_upload(key, upload_id)?
+            .upload_part(part_number, value)
+            .await?;
+
+        Ok(PartInfo {
+            part_number: part.part_number(),
+            etag: part.etag(),
+        })
+    }
+
+    async fn complete_multipart_upload(
+        &self,
+        key: &str,
+        upload_id: &str,
+        parts: Vec<PartInfo>,
+    ) -> Result<ObjectInfo> {
+        let object = self
+            .resume_multipart_upload(key, upload_id)?
+            .complete(
+                parts
+                    .into_iter()
+                    .map(|part| UploadedPart::new(part.part_number, part.etag)),
+            )
+            .await?;
+
+        ObjectInfo::from_object(&object)
+    }
+
+    async fn abort_multipart_upload(&self, key: &str, upload_id: &str) -> Result<()> {
+        self.resume_multipart_upload(key, upload_id)?.abort().await
+    }
+}
@@ -0,0 +1,388 @@
+use std::collections::HashMap;
+
+use futures_util::TryStreamExt;
+use serde::Serialize;
+
+use crate::{Error, FixedLengthStream, Method, Request, Response, Result};
+
+use super::{Bucket, Data, Include, UploadedPart};
+
+/// The largest part number R2 accepts.
+const MAX_PART_NUMBER: u16 = 10_000;
+
+/// The smallest size R2 accepts for every part of a multipart upload but the last.
+const MIN_PART_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Serves resumable uploads of large objects to a [Bucket], mapping requests in the style of the
+/// [S3 multipart upload API](https://docs.aws.amazon.com/AmazonS3/latest/userguide/mpuoverview.html)
+/// to [MultipartUpload](super::MultipartUpload) operations. All responses but aborts have a JSON
+/// body.
+///
+/// | Request                                      | Operation                          |
+/// |----------------------------------------------|------------------------------------|
+/// | `POST /key?uploads`                          | [create](Self::create)             |
+/// | `PUT /key?uploadId=<id>&partNumber=<number>` | [upload_part](Self::upload_part)   |
+/// | `GET /key?uploadId=<id>`                     | [list_parts](Self::list_parts)     |
+/// | `POST /key?uploadId=<id>`                    | [complete](Self::complete)         |
+/// | `DELETE /key?uploadId=<id>`                  | [abort](Self::abort)               |
+///
+/// R2 can't list the parts of an upload, so the handler records each upload and its parts as
+/// empty objects under a [state prefix](Self::state_prefix) of the bucket. This lets a client that
+/// lost track of its progress ask which parts still need to be uploaded, and lets the upload be
+/// completed without the client sending the list of parts.
+///
+/// ```no_run
+/// router.on_async("/uploads/*key", |req, ctx| async move {
+///     let bucket = ctx.bucket("BUCKET")?;
+///     let key = ctx.param("key").unwrap().to_owned();
+///     MultipartUploadHandler::new(&bucket).handle(req, &key).await
+/// })
+/// ```
+pub struct MultipartUploadHandler<'bucket> {
+    bucket: &'bucket Bucket,
+    state_prefix: String,
+}
+
+/// Response body of [create](MultipartUploadHandler::create).
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreatedUpload {
+    key: String,
+    upload_id: String,
+}
+
+/// A part listed by [list_parts](MultipartUploadHandler::list_parts).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Part {
+    part_number: u16,
+    etag: String,
+    size: u64,
+}
+
+/// Response body of [list_parts](MultipartUploadHandler::list_parts).
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ListedParts {
+    key: String,
+    upload_id: String,
+    parts: Vec<Part>,
+}
+
+/// Response body of [complete](MultipartUploadHandler::complete).
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CompletedUpload {
+    key: String,
+    etag: String,
+    size: u64,
+}
+
+impl<'bucket> MultipartUploadHandler<'bucket> {
+    /// Creates a handler for uploads to the given bucket, recording their state under the
+    /// `.multipart-uploads/` prefix.
+    pub fn new(bucket: &'bucket Bucket) -> Self {
+        Self {
+            bucket,
+            state_prefix: ".multipart-uploads/".into(),
+        }
+    }
+
+    /// The prefix of the keys the state of uploads is recorded under. Objects under this prefix
+    /// should not be served to clients, and every operation responds with `403` to keys under it.
+    pub fn state_prefix(mut self, state_prefix: impl Into<String>) -> Self {
+        self.state_prefix = state_prefix.into();
+        self
+    }
+
+    /// Dispatches the request to the operation it maps to, according to its method and query.
+    /// Responds with `400` or `405` to requests that don't map to any operation, and with `403`
+    /// to keys under the [state prefix](Self::state_prefix).
+    pub async fn handle(&self, req: Request, key: &str) -> Result<Response> {
+        if self.is_state_key(key) {
+            return forbidden_key();
+        }
+
+        let query: HashMap<String, String> = req.url()?.query_pairs().into_owned().collect();
+
+        match (req.method(), query.get("uploadId")) {
+            (Method::Post, None) if query.contains_key("uploads") => self.create(key).await,
+            (Method::Put, Some(upload_id)) => {
+                let part_number = match query.get("partNumber").map(|number| number.parse()) {
+                    Some(Ok(part_number)) => part_number,
+                    _ => return Response::error("Invalid part number", 400),
+                };
+                self.upload_part(req, key, upload_id, part_number).await
+            }
+            (Method::Get, Some(upload_id)) => self.list_parts(key, upload_id).await,
+            (Method::Post, Some(upload_id)) => self.complete(key, upload_id).await,
+            (Method::Delete, Some(upload_id)) => self.abort(key, upload_id).await,
+            (Method::Post, None) | (Method::Put, None) | (Method::Get, None) => {
+                Response::error("Missing uploadId", 400)
+            }
+            _ => Response::error("Method Not Allowed", 405),
+        }
+    }
+
+    /// Creates a multipart upload for `key`, responding with its `key` and `uploadId`.
+    pub async fn create(&self, key: &str) -> Result<Response> {
+        if self.is_state_key(key) {
+            return forbidden_key();
+        }
+
+        let upload = self.bucket.create_multipart_upload(key).execute().await?;
+        let upload_id = upload.upload_id();
+
+        let mut metadata = HashMap::new();
+        metadata.insert("key".to_string(), key.to_string());
+        self.bucket
+            .put(self.upload_key(&upload_id), Data::Empty)
+            .custom_metdata(metadata)
+            .execute()
+            .await?;
+
+        Response::from_json(&CreatedUpload {
+            key: key.into(),
+            upload_id,
+        })
+    }
+
+    /// Uploads the request body as part `part_number` of the upload, responding with the
+    /// `partNumber` and `etag` of the part. Uploading a part again replaces it.
+    ///
+    /// The request must have a `Content-Length` header, as R2 needs to know the size of a part
+    /// before it is uploaded.
+    pub async fn upload_part(
+        &self,
+        mut req: Request,
+        key: &str,
+        upload_id: &str,
+        part_number: u16,
+    ) -> Result<Response> {
+        if self.is_state_key(key) {
+            return forbidden_key();
+        }
+        if !(1..=MAX_PART_NUMBER).contains(&part_number) {
+            return Response::error("Invalid part number", 400);
+        }
+
+        let size: u64 = match req.headers().get("content-length")?.map(|len| len.parse()) {
+            Some(Ok(size)) => size,
+            _ => return Response::error("Length Required", 411),
+        };
+
+        if !self.upload_exists(key, upload_id).await? {
+            return Response::error("No such upload", 404);
+        }
+
+        let upload = self.bucket.resume_multipart_upload(key, upload_id)?;
+        let body = FixedLengthStream::wrap(req.stream()?, size);
+        let uploaded = upload.upload_part(part_number, Data::Stream(body)).await?;
+
+        let part = Part {
+            part_number,
+            etag: uploaded.etag(),
+            size,
+        };
+
+        let mut metadata = HashMap::new();
+        metadata.insert("etag".to_string(), part.etag.clone());
+        metadata.insert("size".to_string(), size.to_string());
+        self.bucket
+            .put(self.part_key(upload_id, part_number), Data::Empty)
+            .custom_metdata(metadata)
+            .execute()
+            .await?;
+
+        Response::from_json(&part)
+    }
+
+    /// Responds with the `key` and `uploadId` of the upload, and the `partNumber`, `etag` and
+    /// `size` of each part uploaded so far, ordered by part number.
+    pub async fn list_parts(&self, key: &str, upload_id: &str) -> Result<Response> {
+        if self.is_state_key(key) {
+            return forbidden_key();
+        }
+        if !self.upload_exists(key, upload_id).await? {
+            return Response::error("No such upload", 404);
+        }
+
+        Response::from_json(&ListedParts {
+            key: key.into(),
+            upload_id: upload_id.into(),
+            parts: self.parts(upload_id).await?,
+        })
+    }
+
+    /// Completes the upload with every part uploaded so far, responding with the `key`, `etag` and
+    /// `size` of the resulting object.
+    ///
+    /// Responds with `400` without completing the upload unless the parts are numbered
+    /// consecutively from 1, and all but the last one have the same size of at least 5 MiB, as
+    /// R2 requires.
+    pub async fn complete(&self, key: &str, upload_id: &str) -> Result<Response> {
+        if self.is_state_key(key) {
+            return forbidden_key();
+        }
+        if !self.upload_exists(key, upload_id).await? {
+            return Response::error("No such upload", 404);
+        }
+
+        let parts = self.parts(upload_id).await?;
+        if let Err(reason) = validate_parts(&parts) {
+            return Response::error(reason, 400);
+        }
+
+        let upload = self.bucket.resume_multipart_upload(key, upload_id)?;
+        let object = upload
+            .complete(
+                parts
+                    .iter()
+                    .map(|part| UploadedPart::new(part.part_number, part.etag.clone())),
+            )
+            .await?;
+        self.delete_state(upload_id, &parts).await?;
+
+        Response::from_json(&CompletedUpload {
+            key: key.into(),
+            etag: object.http_etag(),
+            size: object.size(),
+        })
+    }
+
+    /// Aborts the upload, discarding every part uploaded so far. Responds with `204`.
+    pub async fn abort(&self, key: &str, upload_id: &str) -> Result<Response> {
+        if self.is_state_key(key) {
+            return forbidden_key();
+        }
+        if !self.upload_exists(key, upload_id).await? {
+            return Response::error("No such upload", 404);
+        }
+
+        let parts = self.parts(upload_id).await?;
+        self.bucket
+            .resume_multipart_upload(key, upload_id)?
+            .abort()
+            .await?;
+        self.delete_state(upload_id, &parts).await?;
+
+        Ok(Response::empty()?.with_status(204))
+    }
+
+    /// Whether `key` is under the state prefix, where clients must not be able to create or
+    /// overwrite objects that would corrupt the state of uploads.
+    fn is_state_key(&self, key: &str) -> bool {
+        key.starts_with(&self.state_prefix)
+    }
+
+    fn upload_key(&self, upload_id: &str) -> String {
+        format!("{}{}", self.state_prefix, upload_id)
+    }
+
+    fn part_key(&self, upload_id: &str, part_number: u16) -> String {
+        // Zero-padded, so parts are listed in order.
+        format!("{}/{:05}", self.upload_key(upload_id), part_number)
+    }
+
+    /// Whether an upload with the given id was created for `key` by this handler, and hasn't been
+    /// completed or aborted since.
+    async fn upload_exists(&self, key: &str, upload_id: &str) -> Result<bool> {
+        Ok(match self.bucket.head(self.upload_key(upload_id)).await? {
+            Some(object) => object.custom_metadata()?.get("key").map(String::as_str) == Some(key),
+            None => false,
+        })
+    }
+
+    async fn parts(&self, upload_id: &str) -> Result<Vec<Part>> {
+        let objects: Vec<_> = self
+            .bucket
+            .list()
+            .prefix(format!("{}/", self.upload_key(upload_id)))
+            .include(vec![Include::CustomMetadata])
+            .stream()
+            .try_collect()
+            .await?;
+
+        objects
+            .into_iter()
+            .map(|object| {
+                let part_number = object.key().rsplit('/').next().and_then(|n| n.parse().ok());
+                let metadata = object.custom_metadata()?;
+                let size = metadata.get("size").and_then(|size| size.parse().ok());
+                match (part_number, metadata.get("etag"), size) {
+                    (Some(part_number), Some(etag), Some(size)) => Ok(Part {
+                        part_number,
+                        etag: etag.clone(),
+                        size,
+                    }),
+                    _ => Err(Error::RustError(format!(
+                        "invalid multipart upload state `{}`",
+                        object.key()
+                    ))),
+                }
+            })
+            .collect()
+    }
+
+    async fn delete_state(&self, upload_id: &str, parts: &[Part]) -> Result<()> {
+        let keys = parts
+            .iter()
+            .map(|part| self.part_key(upload_id, part.part_number))
+            .chain(Some(self.upload_key(upload_id)));
+        self.bucket.delete_many(keys).await
+    }
+}
+
+fn forbidden_key() -> Result<Response> {
+    Response::error("Uploads to this key are not allowed", 403)
+}
+
+/// Checks that the parts, ordered by part number, can make up an object.
+fn validate_parts(parts: &[Part]) -> std::result::Result<(), &'static str> {
+    let (last, rest) = match parts.split_last() {
+        Some(split) => split,
+        None => return Err("No parts were uploaded"),
+    };
+
+    if parts
+        .iter()
+        .zip(1..)
+        .any(|(part, number)| part.part_number != number)
+    {
+        return Err("Parts must be numbered consecutively from 1");
+    }
+
+    if let Some(first) = rest.first() {
+        if first.size < MIN_PART_SIZE || rest.iter().any(|part| part.size != first.size) {
+            return Err("All parts but the last must have the same size of at least 5 MiB");
+        }
+        if last.size > first.size {
+            return Err("The last part must not be larger than the other parts");
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn validate_parts_works() {
+    let part = |part_number, size| Part {
+        part_number,
+        etag: String::new(),
+        size,
+    };
+
+    assert!(validate_parts(&[part(1, 10)]).is_ok());
+    assert!(validate_parts(&[part(1, MIN_PART_SIZE), part(2, MIN_PART_SIZE), part(3, 1)]).is_ok());
+    assert!(validate_parts(&[]).is_err());
+    assert!(validate_parts(&[part(2, 10)]).is_err());
+    assert!(validate_parts(&[part(1, MIN_PART_SIZE), part(3, 1)]).is_err());
+    assert!(validate_parts(&[part(1, 10), part(2, 1)]).is_err());
+    assert!(validate_parts(&[part(1, MIN_PART_SIZE), part(2, MIN_PART_SIZE + 1)]).is_err());
+    assert!(validate_parts(&[
+        part(1, MIN_PART_SIZE),
+        part(2, MIN_PART_SIZE + 1),
+        part(3, 1)
+    ])
+    .is_err());
+}
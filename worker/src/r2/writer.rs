@@ -0,0 +1,311 @@
+use std::{
+    io, mem,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_util::{
+    future::{self, LocalBoxFuture},
+    io::AsyncWrite,
+    ready,
+    stream::{FuturesUnordered, StreamExt},
+    Stream,
+};
+use wasm_bindgen_futures::JsFuture;
+
+use crate::{Error, Result};
+
+use super::{Data, MultipartUpload, Object, UploadedPart};
+
+/// The largest number of parts a multipart upload can consist of.
+const MAX_PARTS: u16 = 10_000;
+
+/// Streams bytes into a [MultipartUpload], taking care of splitting them into parts, numbering
+/// and uploading those parts, and completing the upload.
+///
+/// Bytes are buffered until a whole part is available, and up to [concurrency](Self::concurrency)
+/// parts are uploaded at the same time. If writing a part or completing the upload fails, the
+/// multipart upload is aborted and the writer can no longer be used.
+///
+/// The writer also implements [AsyncWrite], so it can be the destination of
+/// [futures_util::io::copy].
+///
+/// ```no_run
+/// let upload = bucket.create_multipart_upload("large-file").execute().await?;
+/// let object = R2Writer::new(upload)
+///     .part_size(16 * 1024 * 1024)
+///     .upload(req.stream()?)
+///     .await?;
+/// ```
+pub struct R2Writer {
+    upload: MultipartUpload,
+    part_size: usize,
+    concurrency: usize,
+    buffer: Vec<u8>,
+    next_part_number: u16,
+    in_flight: FuturesUnordered<LocalBoxFuture<'static, Result<UploadedPart>>>,
+    uploaded_parts: Vec<UploadedPart>,
+    completing: Option<LocalBoxFuture<'static, Result<Object>>>,
+    object: Option<Object>,
+    aborted: bool,
+    aborting: Option<LocalBoxFuture<'static, ()>>,
+    error: Option<Error>,
+}
+
+impl R2Writer {
+    /// The smallest part size R2 accepts for every part of a multipart upload but the last.
+    pub const MIN_PART_SIZE: usize = 5 * 1024 * 1024;
+
+    /// Creates a writer for the given multipart upload, uploading parts of
+    /// [MIN_PART_SIZE](Self::MIN_PART_SIZE) bytes four at a time.
+    pub fn new(upload: MultipartUpload) -> Self {
+        Self {
+            upload,
+            part_size: Self::MIN_PART_SIZE,
+            concurrency: 4,
+            buffer: Vec::new(),
+            next_part_number: 1,
+            in_flight: FuturesUnordered::new(),
+            uploaded_parts: Vec::new(),
+            completing: None,
+            object: None,
+            aborted: false,
+            aborting: None,
+            error: None,
+        }
+    }
+
+    /// The size of every part but the last, which may be smaller. R2 requires all those parts to
+    /// be the same size and at least [MIN_PART_SIZE](Self::MIN_PART_SIZE) bytes, so smaller sizes
+    /// are rounded up to it.
+    ///
+    /// Each part is buffered in memory until it is uploaded, so this bounds, together with
+    /// [concurrency](Self::concurrency), how much memory the writer uses.
+    pub fn part_size(mut self, part_size: usize) -> Self {
+        self.part_size = part_size.max(Self::MIN_PART_SIZE);
+        self
+    }
+
+    /// The maximum number of parts uploaded at the same time. Defaults to 4.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Writes bytes to the upload, uploading every part that has been filled in the process.
+    pub async fn write(&mut self, mut bytes: &[u8]) -> Result<()> {
+        while !bytes.is_empty() {
+            let written =
+                future::poll_fn(|cx| self.poll_or_abort(cx, |w, cx| w.poll_write_part(cx, bytes)))
+                    .await?;
+            bytes = &bytes[written..];
+        }
+        Ok(())
+    }
+
+    /// Uploads the remaining buffered bytes as the last part, waits for every part to be uploaded
+    /// and completes the multipart upload. This also returns the uploaded object when the writer
+    /// was already closed through [AsyncWrite].
+    pub async fn finish(mut self) -> Result<Object> {
+        future::poll_fn(|cx| self.poll_or_abort(cx, Self::poll_complete)).await?;
+        self.object
+            .take()
+            .ok_or_else(|| Error::RustError("multipart upload was not completed".into()))
+    }
+
+    /// Writes every chunk of the stream to the upload and completes it.
+    pub async fn upload<S>(mut self, stream: S) -> Result<Object>
+    where
+        S: Stream<Item = Result<Vec<u8>>>,
+    {
+        futures_util::pin_mut!(stream);
+        while let Some(chunk) = stream.next().await {
+            match chunk {
+                Ok(chunk) => self.write(&chunk).await?,
+                Err(e) => {
+                    self.start_abort(e);
+                    return Err(future::poll_fn(|cx| self.poll_aborted(cx)).await);
+                }
+            }
+        }
+
+        self.finish().await
+    }
+
+    /// Aborts the multipart upload, discarding every part uploaded so far.
+    pub async fn abort(self) -> Result<()> {
+        self.upload.abort().await
+    }
+
+    /// Runs `poll`, aborting the upload if it fails. Once aborted, the error is returned after
+    /// the abort finished, and every later call fails.
+    fn poll_or_abort<T>(
+        &mut self,
+        cx: &mut Context<'_>,
+        poll: impl FnOnce(&mut Self, &mut Context<'_>) -> Poll<Result<T>>,
+    ) -> Poll<Result<T>> {
+        if !self.aborted {
+            match ready!(poll(self, cx)) {
+                Ok(value) => return Poll::Ready(Ok(value)),
+                Err(e) => self.start_abort(e),
+            }
+        }
+        self.poll_aborted(cx).map(Err)
+    }
+
+    /// Starts aborting the upload after `error` occurred, which is returned by
+    /// [poll_aborted](Self::poll_aborted). A failure to abort is ignored since the original error
+    /// is more useful to the caller.
+    fn start_abort(&mut self, error: Error) {
+        let upload = MultipartUpload {
+            inner: self.upload.inner.clone(),
+        };
+        self.aborted = true;
+        self.in_flight = FuturesUnordered::new();
+        self.completing = None;
+        self.aborting = Some(Box::pin(async move {
+            let _ = upload.abort().await;
+        }));
+        self.error = Some(error);
+    }
+
+    fn poll_aborted(&mut self, cx: &mut Context<'_>) -> Poll<Error> {
+        if let Some(aborting) = &mut self.aborting {
+            ready!(aborting.as_mut().poll(cx));
+            self.aborting = None;
+        }
+        Poll::Ready(
+            self.error
+                .take()
+                .unwrap_or_else(|| Error::RustError("multipart upload was aborted".into())),
+        )
+    }
+
+    /// Copies as much of `bytes` as fits into the current part, first uploading the buffered part
+    /// if it is full.
+    fn poll_write_part(&mut self, cx: &mut Context<'_>, bytes: &[u8]) -> Poll<Result<usize>> {
+        if self.buffer.len() >= self.part_size {
+            ready!(self.poll_upload_buffer(cx))?;
+        }
+
+        let written = bytes.len().min(self.part_size - self.buffer.len());
+        self.buffer.extend_from_slice(&bytes[..written]);
+
+        // Start uploading a filled part right away when there is room for it.
+        if self.buffer.len() >= self.part_size && self.in_flight.len() < self.concurrency {
+            self.upload_buffer()?;
+        }
+        Poll::Ready(Ok(written))
+    }
+
+    /// Uploads every filled part and waits for the parts in flight to be uploaded.
+    fn poll_flush_parts(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        if self.buffer.len() >= self.part_size {
+            ready!(self.poll_upload_buffer(cx))?;
+        }
+        while let Some(part) = ready!(self.in_flight.poll_next_unpin(cx)) {
+            self.uploaded_parts.push(part?);
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_complete(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        if self.object.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+
+        if self.completing.is_none() {
+            // An upload has at least one part, even if nothing was written to it.
+            if !self.buffer.is_empty() || self.next_part_number == 1 {
+                ready!(self.poll_upload_buffer(cx))?;
+            }
+            ready!(self.poll_flush_parts(cx))?;
+
+            let mut uploaded_parts = mem::take(&mut self.uploaded_parts);
+            uploaded_parts.sort_by_key(UploadedPart::part_number);
+
+            let upload = MultipartUpload {
+                inner: self.upload.inner.clone(),
+            };
+            self.completing = Some(Box::pin(upload.complete(uploaded_parts)));
+        }
+
+        if let Some(completing) = &mut self.completing {
+            let object = ready!(completing.as_mut().poll(cx))?;
+            self.completing = None;
+            self.object = Some(object);
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Uploads the buffered bytes as the next part once fewer than
+    /// [concurrency](Self::concurrency) parts are in flight.
+    fn poll_upload_buffer(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        while self.in_flight.len() >= self.concurrency {
+            match ready!(self.in_flight.poll_next_unpin(cx)) {
+                Some(part) => self.uploaded_parts.push(part?),
+                None => break,
+            }
+        }
+        Poll::Ready(self.upload_buffer())
+    }
+
+    fn upload_buffer(&mut self) -> Result<()> {
+        if self.next_part_number > MAX_PARTS {
+            return Err(Error::RustError(format!(
+                "a multipart upload can have at most {MAX_PARTS} parts"
+            )));
+        }
+
+        let part_number = self.next_part_number;
+        self.next_part_number += 1;
+
+        let part = mem::take(&mut self.buffer);
+        let promise = self
+            .upload
+            .inner
+            .upload_part(part_number, Data::Bytes(part).into());
+        self.in_flight.push(Box::pin(async move {
+            let uploaded_part = JsFuture::from(promise).await?;
+            Ok(UploadedPart {
+                inner: uploaded_part.into(),
+            })
+        }));
+
+        Ok(())
+    }
+}
+
+/// Writes through [AsyncWrite] behave like [write](R2Writer::write). Flushing waits for every
+/// filled part to be uploaded, while the bytes of an incomplete part stay buffered since R2 requires
+/// all but the last part to be the same size. Closing completes the upload, after which
+/// [finish](R2Writer::finish) returns the uploaded object.
+///
+/// ```no_run
+/// let mut writer = R2Writer::new(upload);
+/// futures_util::io::copy(reader, &mut writer).await?;
+/// let object = writer.finish().await?;
+/// ```
+impl AsyncWrite for R2Writer {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut()
+            .poll_or_abort(cx, |w, cx| w.poll_write_part(cx, buf))
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut()
+            .poll_or_abort(cx, Self::poll_flush_parts)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut()
+            .poll_or_abort(cx, Self::poll_complete)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))
+    }
+}
@@ -1,11 +1,14 @@
 use std::convert::TryFrom;
 
 use crate::{
-    cf::Cf, error::Error, headers::Headers, http::Method, ByteStream, FormData, RequestInit, Result,
+    cf::Cf, cookie::parse_cookie_header, error::Error, headers::Headers, http::Method,
+    response::ResponseInit, ByteStream, FormData, RequestInit, Result,
 };
 
+use futures_util::TryStreamExt;
 use serde::de::DeserializeOwned;
 use std::borrow::Cow;
+use std::collections::HashMap;
 use url::{form_urlencoded::Parse, Url};
 use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::JsFuture;
@@ -21,6 +24,7 @@ pub struct Request {
     cf: Cf,
     edge_request: web_sys::Request,
     body_used: bool,
+    body_limit: Option<u64>,
     immutable: bool,
 }
 
@@ -41,6 +45,7 @@ impl From<web_sys::Request> for Request {
             cf: req.cf().into(),
             edge_request: req,
             body_used: false,
+            body_limit: None,
             immutable: true,
         }
     }
@@ -98,6 +103,9 @@ impl Request {
 
     /// Access this request's body encoded as JSON.
     pub async fn json<B: DeserializeOwned>(&mut self) -> Result<B> {
+        if let Some(limit) = self.body_limit {
+            return self.json_limited(limit).await;
+        }
         if !self.body_used {
             self.body_used = true;
             return JsFuture::from(self.edge_request.json()?)
@@ -116,6 +124,9 @@ impl Request {
 
     /// Access this request's body as plaintext.
     pub async fn text(&mut self) -> Result<String> {
+        if let Some(limit) = self.body_limit {
+            return self.text_limited(limit).await;
+        }
         if !self.body_used {
             self.body_used = true;
             return JsFuture::from(self.edge_request.text()?)
@@ -134,6 +145,9 @@ impl Request {
 
     /// Access this request's body as raw bytes.
     pub async fn bytes(&mut self) -> Result<Vec<u8>> {
+        if let Some(limit) = self.body_limit {
+            return self.bytes_limited(limit).await;
+        }
         if !self.body_used {
             self.body_used = true;
             return JsFuture::from(self.edge_request.array_buffer()?)
@@ -152,6 +166,9 @@ impl Request {
 
     /// Access this request's body as a form-encoded payload and pull out fields and files.
     pub async fn form_data(&mut self) -> Result<FormData> {
+        if let Some(limit) = self.body_limit {
+            return self.form_data_limited(limit).await;
+        }
         if !self.body_used {
             self.body_used = true;
             return JsFuture::from(self.edge_request.form_data()?)
@@ -168,6 +185,88 @@ impl Request {
         Err(Error::BodyUsed)
     }
 
+    /// Access this request's body encoded as JSON, returning [Error::BodyTooLarge] if the body is
+    /// larger than `limit` bytes.
+    pub async fn json_limited<B: DeserializeOwned>(&mut self, limit: u64) -> Result<B> {
+        serde_json::from_slice(&self.bytes_limited(limit).await?).map_err(Error::from)
+    }
+
+    /// Access this request's body as plaintext, returning [Error::BodyTooLarge] if the body is
+    /// larger than `limit` bytes.
+    pub async fn text_limited(&mut self, limit: u64) -> Result<String> {
+        String::from_utf8(self.bytes_limited(limit).await?)
+            .map_err(|e| Error::RustError(e.to_string()))
+    }
+
+    /// Access this request's body as raw bytes, returning [Error::BodyTooLarge] if the body is
+    /// larger than `limit` bytes. The body is rejected before being read if its `Content-Length`
+    /// exceeds the limit, and reading stops as soon as the limit is exceeded otherwise.
+    pub async fn bytes_limited(&mut self, limit: u64) -> Result<Vec<u8>> {
+        if self.body_used {
+            return Err(Error::BodyUsed);
+        }
+
+        let content_length = self.headers.get("content-length")?;
+        if let Some(Ok(length)) = content_length.map(|length| length.parse::<u64>()) {
+            if length > limit {
+                return Err(Error::BodyTooLarge(limit));
+            }
+        }
+
+        if self.edge_request.body().is_none() {
+            self.body_used = true;
+            return Ok(Vec::new());
+        }
+
+        let mut stream = self.stream()?;
+        let mut bytes = Vec::new();
+        while let Some(chunk) = stream.try_next().await? {
+            if (bytes.len() + chunk.len()) as u64 > limit {
+                return Err(Error::BodyTooLarge(limit));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        Ok(bytes)
+    }
+
+    /// Access this request's body as a form-encoded payload, returning [Error::BodyTooLarge] if
+    /// the body is larger than `limit` bytes.
+    pub async fn form_data_limited(&mut self, limit: u64) -> Result<FormData> {
+        let mut headers = Headers::new();
+        if let Some(content_type) = self.headers.get("content-type")? {
+            headers.set("content-type", &content_type)?;
+        }
+        let mut bytes = self.bytes_limited(limit).await?;
+
+        // The body was consumed to enforce the limit, so it's parsed from a response holding the
+        // bytes that were read instead.
+        let init = ResponseInit {
+            status: 200,
+            headers,
+            websocket: None,
+        };
+        let buffered =
+            web_sys::Response::new_with_opt_u8_array_and_init(Some(&mut bytes), &init.into())?;
+
+        JsFuture::from(buffered.form_data()?)
+            .await
+            .map(|val| val.into())
+            .map_err(Error::from)
+    }
+
+    /// Limits the size of the bodies read by [json](Self::json), [text](Self::text),
+    /// [bytes](Self::bytes) and [form_data](Self::form_data) to `limit` bytes, past which they
+    /// return [Error::BodyTooLarge]. Bodies read with [stream](Self::stream) are not limited.
+    pub fn set_body_limit(&mut self, limit: u64) {
+        self.body_limit = Some(limit);
+    }
+
+    /// The limit set with [set_body_limit](Self::set_body_limit), if any.
+    pub fn body_limit(&self) -> Option<u64> {
+        self.body_limit
+    }
+
     /// Access this request's body as a [`Stream`](futures::stream::Stream) of bytes.
     pub fn stream(&mut self) -> Result<ByteStream> {
         if self.body_used {
@@ -192,6 +291,20 @@ impl Request {
         &self.headers
     }
 
+    /// The cookies sent with this request in its `Cookie` header, by name.
+    pub fn cookies(&self) -> Result<HashMap<String, String>> {
+        Ok(self
+            .headers
+            .get("cookie")?
+            .map(|header| parse_cookie_header(&header))
+            .unwrap_or_default())
+    }
+
+    /// The value of the cookie with the given name sent with this request, if any.
+    pub fn cookie(&self, name: &str) -> Result<Option<String>> {
+        Ok(self.cookies()?.remove(name))
+    }
+
     /// Get a mutable reference to this request's `Headers`.
     /// **Note:** they can only be modified if the request was created from scratch or cloned.
     pub fn headers_mut(&mut self) -> Result<&mut Headers> {
@@ -238,15 +351,15 @@ impl Request {
 
     #[allow(clippy::should_implement_trait)]
     pub fn clone(&self) -> Result<Self> {
-        self.edge_request
-            .clone()
-            .map(|req| req.into())
-            .map_err(Error::from)
+        let mut req: Request = self.edge_request.clone().map_err(Error::from)?.into();
+        req.body_limit = self.body_limit;
+        Ok(req)
     }
 
     pub fn clone_mut(&self) -> Result<Self> {
         let mut req: Request = web_sys::Request::new_with_request(&self.edge_request)?.into();
         req.immutable = false;
+        req.body_limit = self.body_limit;
         Ok(req)
     }
 
@@ -2,13 +2,18 @@ use std::collections::HashMap;
 
 use crate::headers::Headers;
 use crate::http::Method;
+use crate::streams::to_readable_stream;
+use crate::{AbortSignal, Error, FormData, Result};
 
-use js_sys::{self, Object};
+use futures_util::TryStream;
+use js_sys::{self, Object, Uint8Array};
+use serde::Serialize;
 use wasm_bindgen::{prelude::*, JsValue};
 
 /// Optional options struct that contains settings to apply to the `Request`.
 pub struct RequestInit {
-    /// Currently requires a manual conversion from your data into a [`wasm_bindgen::JsValue`].
+    /// The body of the request, usually set with a typed setter such as
+    /// [with_json](Self::with_json), or from a manually converted [`wasm_bindgen::JsValue`].
     pub body: Option<JsValue>,
     /// Headers associated with the outbound `Request`.
     pub headers: Headers,
@@ -21,6 +26,17 @@ pub struct RequestInit {
     /// follow. Note, however, that the incoming Request property of a FetchEvent will have redirect
     /// mode manual.
     pub redirect: RequestRedirect,
+    /// The signal with which the request can be aborted. Refer to
+    /// [AbortController](crate::AbortController).
+    pub signal: Option<AbortSignal>,
+    /// How the request interacts with the cache. Leaves the runtime's default when `None`. Refer
+    /// to [with_cache](Self::with_cache).
+    pub cache: Option<RequestCache>,
+    /// The [subresource integrity](https://developer.mozilla.org/en-US/docs/Web/Security/Subresource_Integrity)
+    /// metadata that the response body must match, such as `sha256-...`.
+    pub integrity: Option<String>,
+    /// Whether the response can be received while the request body is still being sent.
+    pub duplex: Option<RequestDuplex>,
 }
 
 impl RequestInit {
@@ -52,6 +68,92 @@ impl RequestInit {
         self.cf = props;
         self
     }
+
+    /// Sets the signal with which the request can be aborted, such as when sending it with
+    /// [Fetcher::fetch_request](crate::Fetcher::fetch_request) or a Durable Object stub.
+    pub fn with_signal(&mut self, signal: Option<&AbortSignal>) -> &mut Self {
+        self.signal = signal.map(|signal| AbortSignal::from((**signal).clone()));
+        self
+    }
+
+    /// Sets how the request interacts with the cache. The Workers runtime only accepts
+    /// [RequestCache::NoStore], with the `cache_option_enabled` compatibility flag or a
+    /// compatibility date of 2024-11-11 or later.
+    pub fn with_cache(&mut self, cache: RequestCache) -> &mut Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Sets the [subresource integrity](https://developer.mozilla.org/en-US/docs/Web/Security/Subresource_Integrity)
+    /// metadata, such as `sha256-...`, that the response body must match. The Workers runtime
+    /// doesn't implement these checks, and fails to create requests with non-empty metadata.
+    pub fn with_integrity(&mut self, integrity: impl Into<String>) -> &mut Self {
+        self.integrity = Some(integrity.into());
+        self
+    }
+
+    /// Sets whether the response can be received while the request body is still being sent.
+    pub fn with_duplex(&mut self, duplex: RequestDuplex) -> &mut Self {
+        self.duplex = Some(duplex);
+        self
+    }
+
+    /// Sets the body to `value` encoded as JSON, with an `application/json` content type unless
+    /// one is already set. As [with_headers](Self::with_headers) replaces all headers, it should
+    /// be called before any of the typed body setters.
+    pub fn with_json<T: Serialize>(&mut self, value: &T) -> Result<&mut Self> {
+        let json = serde_json::to_string(value)?;
+        self.with_typed_body(json.into(), "application/json")
+    }
+
+    /// Sets the body to `text`, with a `text/plain;charset=UTF-8` content type unless one is
+    /// already set.
+    pub fn with_text(&mut self, text: impl AsRef<str>) -> Result<&mut Self> {
+        self.with_typed_body(text.as_ref().into(), "text/plain;charset=UTF-8")
+    }
+
+    /// Sets the body to `bytes`, with an `application/octet-stream` content type unless one is
+    /// already set.
+    pub fn with_bytes(&mut self, bytes: impl AsRef<[u8]>) -> Result<&mut Self> {
+        let array = Uint8Array::from(bytes.as_ref());
+        self.with_typed_body(array.into(), "application/octet-stream")
+    }
+
+    /// Sets the body to `form`, encoded as `multipart/form-data`. The content type, which
+    /// includes the boundary between the fields, is set by the runtime when sending the request,
+    /// so no `Content-Type` header should be set.
+    pub fn with_form(&mut self, form: FormData) -> &mut Self {
+        self.body = Some(form.into());
+        self
+    }
+
+    /// Sets the body to `value` encoded as a URL-encoded form, with an
+    /// `application/x-www-form-urlencoded` content type unless one is already set.
+    pub fn with_url_encoded<T: Serialize>(&mut self, value: &T) -> Result<&mut Self> {
+        let encoded =
+            serde_urlencoded::to_string(value).map_err(|e| Error::RustError(e.to_string()))?;
+        self.with_typed_body(encoded.into(), "application/x-www-form-urlencoded")
+    }
+
+    /// Sets the body to the bytes of `stream`, sent as they are produced, with an
+    /// `application/octet-stream` content type unless one is already set.
+    pub fn with_stream<S>(&mut self, stream: S) -> Result<&mut Self>
+    where
+        S: TryStream + 'static,
+        S::Ok: Into<Vec<u8>>,
+        S::Error: Into<Error>,
+    {
+        let stream = to_readable_stream(stream);
+        self.with_typed_body(stream.into(), "application/octet-stream")
+    }
+
+    fn with_typed_body(&mut self, body: JsValue, content_type: &str) -> Result<&mut Self> {
+        if !self.headers.has("content-type")? {
+            self.headers.set("content-type", content_type)?;
+        }
+        self.body = Some(body);
+        Ok(self)
+    }
 }
 
 impl From<&RequestInit> for web_sys::RequestInit {
@@ -62,17 +164,31 @@ impl From<&RequestInit> for web_sys::RequestInit {
         inner.redirect(req.redirect.into());
         inner.body(req.body.as_ref());
 
+        let set = |key: &str, value: JsValue| {
+            let r = ::js_sys::Reflect::set(inner.as_ref(), &JsValue::from(key), &value);
+            debug_assert!(
+                r.is_ok(),
+                "setting properties should never fail on our dictionary objects"
+            );
+            let _ = r;
+        };
+
         // set the Cloudflare-specific `cf` property on FFI RequestInit
-        let r = ::js_sys::Reflect::set(
-            inner.as_ref(),
-            &JsValue::from("cf"),
-            &JsValue::from(&req.cf),
-        );
-        debug_assert!(
-            r.is_ok(),
-            "setting properties should never fail on our dictionary objects"
-        );
-        let _ = r;
+        set("cf", JsValue::from(&req.cf));
+
+        // only set the options which were given, as the runtime rejects some of their values
+        if let Some(signal) = &req.signal {
+            set("signal", (**signal).clone().into());
+        }
+        if let Some(cache) = req.cache {
+            set("cache", <&str>::from(cache).into());
+        }
+        if let Some(integrity) = &req.integrity {
+            set("integrity", integrity.into());
+        }
+        if let Some(duplex) = req.duplex {
+            set("duplex", <&str>::from(duplex).into());
+        }
 
         inner
     }
@@ -86,6 +202,10 @@ impl Default for RequestInit {
             cf: CfProperties::default(),
             method: Method::Get,
             redirect: RequestRedirect::default(),
+            signal: None,
+            cache: None,
+            integrity: None,
+            duplex: None,
         }
     }
 }
@@ -338,3 +458,36 @@ impl From<RequestRedirect> for web_sys::RequestRedirect {
         }
     }
 }
+
+/// The [cache mode](https://developer.mozilla.org/en-US/docs/Web/API/Request/cache) of a request,
+/// limited to the modes the Workers runtime accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RequestCache {
+    /// The response is fetched without looking it up in, or storing it into, the cache.
+    NoStore,
+}
+
+impl From<RequestCache> for &str {
+    fn from(cache: RequestCache) -> Self {
+        match cache {
+            RequestCache::NoStore => "no-store",
+        }
+    }
+}
+
+/// The [duplex mode](https://developer.mozilla.org/en-US/docs/Web/API/RequestInit#duplex) of a
+/// request, which browsers require to be set when streaming a request body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RequestDuplex {
+    /// The whole request body is sent before the response is received.
+    Half,
+}
+
+impl From<RequestDuplex> for &str {
+    fn from(duplex: RequestDuplex) -> Self {
+        match duplex {
+            RequestDuplex::Half => "half",
+        }
+    }
+}
@@ -1,15 +1,19 @@
+use crate::cookie::Cookie;
 use crate::cors::Cors;
 use crate::error::Error;
 use crate::headers::Headers;
+use crate::sse::Event;
+use crate::streams::to_readable_stream;
 use crate::ByteStream;
 use crate::Result;
 use crate::WebSocket;
 
+use std::fmt::Display;
+use std::result::Result as StdResult;
+
 use futures_util::{TryStream, TryStreamExt};
-use js_sys::Uint8Array;
 use serde::{de::DeserializeOwned, Serialize};
 use wasm_bindgen::JsCast;
-use wasm_bindgen::JsValue;
 use web_sys::ReadableStream;
 use worker_sys::ext::{ResponseExt, ResponseInitExt};
 
@@ -21,6 +25,7 @@ pub enum ResponseBody {
 }
 
 const CONTENT_TYPE: &str = "content-type";
+const SET_COOKIE: &str = "set-cookie";
 
 /// A [Response](https://developer.mozilla.org/en-US/docs/Web/API/Response) representation for
 /// working with or returning a response to a `Request`.
@@ -33,6 +38,12 @@ pub struct Response {
 }
 
 impl Response {
+    /// Start building a `Response` with a [ResponseBuilder], which allows setting the status and
+    /// individual headers before providing the body.
+    pub fn builder() -> ResponseBuilder {
+        ResponseBuilder::new()
+    }
+
     /// Create a `Response` using `B` as the body encoded as JSON. Sets the associated
     /// `Content-Type` header for the `Response` as `application/json`.
     pub fn from_json<B: Serialize>(value: &B) -> Result<Self> {
@@ -111,24 +122,30 @@ impl Response {
         S::Ok: Into<Vec<u8>>,
         S::Error: Into<Error>,
     {
-        let js_stream = stream
-            .map_ok(|item| -> Vec<u8> { item.into() })
-            .map_ok(|chunk| {
-                let array = Uint8Array::new_with_length(chunk.len() as _);
-                array.copy_from(&chunk);
-
-                array.into()
-            })
-            .map_err(|err| -> crate::Error { err.into() })
-            .map_err(|e| JsValue::from(e.to_string()));
-
-        let stream = wasm_streams::ReadableStream::from_stream(js_stream);
-        let stream: ReadableStream = stream.into_raw().dyn_into().unwrap();
+        let stream = to_readable_stream(stream);
 
         let edge_res = web_sys::Response::new_with_opt_readable_stream(Some(&stream))?;
         Ok(Self::from(edge_res))
     }
 
+    /// Create a `Response` streaming server-sent events, such as an
+    /// [SseStream](crate::sse::SseStream). Sets the associated `Content-Type` header for the
+    /// `Response` as `text/event-stream`, and disables caching.
+    pub fn from_sse<S>(events: S) -> Result<Self>
+    where
+        S: TryStream<Ok = Event> + 'static,
+        S::Error: Into<Error>,
+    {
+        let stream = events
+            .map_err(Into::into)
+            .map_ok(|event| event.to_string().into_bytes());
+
+        let mut response = Self::from_stream(stream)?;
+        response.headers.set(CONTENT_TYPE, "text/event-stream")?;
+        response.headers.set("cache-control", "no-cache")?;
+        Ok(response)
+    }
+
     /// Create a `Response` using unprocessed text provided. Sets the associated `Content-Type`
     /// header for the `Response` as `text/plain`.
     pub fn ok(body: impl Into<String>) -> Result<Self> {
@@ -289,6 +306,18 @@ impl Response {
         Ok(self.with_headers(headers))
     }
 
+    /// Appends a `Set-Cookie` header for the given cookie to this response, keeping any cookies
+    /// already set.
+    pub fn with_cookie(mut self, cookie: &Cookie) -> Result<Self> {
+        self.headers.append(SET_COOKIE, &cookie.to_string())?;
+        Ok(self)
+    }
+
+    /// The cookies set by this response with `Set-Cookie` headers, as their raw header values.
+    pub fn set_cookies(&self) -> Vec<String> {
+        self.headers.get_all_set_cookie()
+    }
+
     /// Sets this response's `webSocket` option.
     /// This will require a status code 101 to work.
     pub fn with_websocket(mut self, websocket: Option<WebSocket>) -> Self {
@@ -333,6 +362,237 @@ fn no_using_invalid_error_status_code() {
     assert!(Response::error("399", 399).is_err());
 }
 
+/// A builder for a [Response], created with [Response::builder].
+///
+/// Headers can be set or appended individually, and the builder is terminated by a method
+/// providing the body, such as [json](Self::json) or [html](Self::html), which sets a matching
+/// `Content-Type` header unless one was already set.
+///
+/// ```no_run
+/// # use worker::*;
+/// # fn fetch() -> Result<Response> {
+/// Response::builder()
+///     .status(201)
+///     .header("x-request-id", "42")
+///     .cache_control("no-store")
+///     .json(&serde_json::json!({ "created": true }))
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ResponseBuilder {
+    status_code: u16,
+    headers: Headers,
+    websocket: Option<WebSocket>,
+    error: Option<Error>,
+}
+
+impl Default for ResponseBuilder {
+    fn default() -> Self {
+        Self {
+            status_code: 200,
+            headers: Headers::new(),
+            websocket: None,
+            error: None,
+        }
+    }
+}
+
+impl ResponseBuilder {
+    /// Creates a builder for a response with a 200 status code and no headers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the status code of the response.
+    pub fn status(mut self, status_code: u16) -> Self {
+        self.status_code = status_code;
+        self
+    }
+
+    /// Appends a header to the response, keeping any values already set for the same name.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        let result = self.headers.append(name, value);
+        self.record(result)
+    }
+
+    /// Sets a header of the response, replacing any values already set for the same name.
+    pub fn set_header(mut self, name: &str, value: &str) -> Self {
+        let result = self.headers.set(name, value);
+        self.record(result)
+    }
+
+    /// Replaces all headers of the response.
+    pub fn headers(mut self, headers: Headers) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Appends a `Set-Cookie` header to the response, such as one formatted from a [Cookie].
+    pub fn cookie(self, cookie: impl Display) -> Self {
+        self.header(SET_COOKIE, &cookie.to_string())
+    }
+
+    /// Sets the `Cache-Control` header of the response.
+    pub fn cache_control(self, value: &str) -> Self {
+        self.set_header("cache-control", value)
+    }
+
+    /// Sets the `Content-Type` header of the response.
+    pub fn content_type(self, value: &str) -> Self {
+        self.set_header(CONTENT_TYPE, value)
+    }
+
+    /// Sets the `webSocket` option of the response, which requires a 101 status code.
+    pub fn websocket(mut self, websocket: WebSocket) -> Self {
+        self.websocket = Some(websocket);
+        self
+    }
+
+    /// Builds the response with `value` encoded as JSON as its body, with an `application/json`
+    /// content type.
+    pub fn json<B: Serialize>(self, value: &B) -> Result<Response> {
+        let data = serde_json::to_vec(value)
+            .map_err(|_| Error::Json(("Failed to encode data to json".into(), 500)))?;
+        self.with_content_type("application/json")?
+            .build(ResponseBody::Body(data))
+    }
+
+    /// Builds the response with `html` as its body, with a `text/html` content type.
+    pub fn html(self, html: impl AsRef<str>) -> Result<Response> {
+        let data = html.as_ref().as_bytes().to_vec();
+        self.with_content_type("text/html")?
+            .build(ResponseBody::Body(data))
+    }
+
+    /// Builds the response with `text` as its body, with a `text/plain` content type.
+    pub fn text(self, text: impl Into<String>) -> Result<Response> {
+        let data = text.into().into_bytes();
+        self.with_content_type("text/plain")?
+            .build(ResponseBody::Body(data))
+    }
+
+    /// Builds the response with `bytes` as its body. No content type is set by default.
+    pub fn body(self, bytes: impl Into<Vec<u8>>) -> Result<Response> {
+        self.build(ResponseBody::Body(bytes.into()))
+    }
+
+    /// Builds the response with `stream` as its body. No content type is set by default.
+    pub fn stream<S>(self, stream: S) -> Result<Response>
+    where
+        S: TryStream + 'static,
+        S::Ok: Into<Vec<u8>>,
+        S::Error: Into<Error>,
+    {
+        let body = Response::from_stream(stream)?.body;
+        self.build(body)
+    }
+
+    /// Builds the response without a body.
+    pub fn empty(self) -> Result<Response> {
+        self.build(ResponseBody::Empty)
+    }
+
+    fn record(mut self, result: Result<()>) -> Self {
+        if let Err(e) = result {
+            self.error.get_or_insert(e);
+        }
+        self
+    }
+
+    fn with_content_type(mut self, content_type: &str) -> Result<Self> {
+        if !self.headers.has(CONTENT_TYPE)? {
+            self.headers.set(CONTENT_TYPE, content_type)?;
+        }
+        Ok(self)
+    }
+
+    fn build(self, body: ResponseBody) -> Result<Response> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+
+        Ok(Response {
+            body,
+            headers: self.headers,
+            status_code: self.status_code,
+            websocket: self.websocket,
+        })
+    }
+}
+
+/// A type that can be converted into a [Response], so that handlers can return lightweight values
+/// and convert them at the end with [into_response](Self::into_response).
+///
+/// Strings become `text/plain` responses, bytes become `application/octet-stream` responses, a
+/// `(u16, T)` tuple overrides the status code of the response of `T`, and the error of a `Result`
+/// is propagated as an [Error].
+///
+/// ```no_run
+/// # use worker::*;
+/// fn greet(name: Option<&str>) -> (u16, String) {
+///     match name {
+///         Some(name) => (200, format!("Hello, {name}!")),
+///         None => (400, "missing name".into()),
+///     }
+/// }
+///
+/// # fn fetch(req: Request) -> Result<Response> {
+/// greet(req.url()?.query()).into_response()
+/// # }
+/// ```
+pub trait IntoResponse {
+    fn into_response(self) -> Result<Response>;
+}
+
+impl IntoResponse for Response {
+    fn into_response(self) -> Result<Response> {
+        Ok(self)
+    }
+}
+
+impl IntoResponse for ResponseBuilder {
+    fn into_response(self) -> Result<Response> {
+        self.empty()
+    }
+}
+
+impl IntoResponse for String {
+    fn into_response(self) -> Result<Response> {
+        Response::ok(self)
+    }
+}
+
+impl IntoResponse for &str {
+    fn into_response(self) -> Result<Response> {
+        Response::ok(self)
+    }
+}
+
+impl IntoResponse for Vec<u8> {
+    fn into_response(self) -> Result<Response> {
+        Response::from_bytes(self)
+    }
+}
+
+impl IntoResponse for () {
+    fn into_response(self) -> Result<Response> {
+        Response::empty()
+    }
+}
+
+impl<T: IntoResponse> IntoResponse for (u16, T) {
+    fn into_response(self) -> Result<Response> {
+        let (status_code, value) = self;
+        Ok(value.into_response()?.with_status(status_code))
+    }
+}
+
+impl<T: IntoResponse, E: Into<Error>> IntoResponse for StdResult<T, E> {
+    fn into_response(self) -> Result<Response> {
+        self.map_err(Into::into)?.into_response()
+    }
+}
+
 pub struct ResponseInit {
     pub status: u16,
     pub headers: Headers,
@@ -10,7 +10,7 @@ use crate::{
     http::Method,
     request::Request,
     response::Response,
-    Bucket, Fetcher, Result,
+    Bucket, Error, Fetcher, Result,
 };
 
 type HandlerFn<D> = fn(Request, RouteContext<D>) -> Result<Response>;
@@ -45,6 +45,7 @@ impl<D> Clone for Handler<'_, D> {
 pub struct Router<'a, D> {
     handlers: HashMap<Method, Node<Handler<'a, D>>>,
     or_else_any_method: Node<Handler<'a, D>>,
+    body_limit: Option<u64>,
     data: D,
 }
 
@@ -121,10 +122,19 @@ impl<'a, D: 'a> Router<'a, D> {
         Self {
             handlers: HashMap::new(),
             or_else_any_method: Node::new(),
+            body_limit: None,
             data,
         }
     }
 
+    /// Limit the size of the bodies of all requests handled by this `Router` to `limit` bytes.
+    /// Reading a larger body returns [Error::BodyTooLarge], which the `Router` responds to with a
+    /// 413 status code. Refer to [Request::set_body_limit].
+    pub fn with_body_limit(mut self, limit: u64) -> Self {
+        self.body_limit = Some(limit);
+        self
+    }
+
     /// Register an HTTP handler that will exclusively respond to HEAD requests.
     pub fn head(mut self, pattern: &str, func: HandlerFn<D>) -> Self {
         self.add_handler(pattern, Handler::Sync(func), vec![Method::Head]);
@@ -167,12 +177,19 @@ impl<'a, D: 'a> Router<'a, D> {
         self
     }
 
-    /// Register an HTTP handler that will respond to any requests.
+    /// Register an HTTP handler that will respond to any requests with a standard method.
     pub fn on(mut self, pattern: &str, func: HandlerFn<D>) -> Self {
         self.add_handler(pattern, Handler::Sync(func), Method::all());
         self
     }
 
+    /// Register an HTTP handler that will exclusively respond to requests with the given method,
+    /// such as an extension method like `Method::from("PURGE")`.
+    pub fn on_method(mut self, method: Method, pattern: &str, func: HandlerFn<D>) -> Self {
+        self.add_handler(pattern, Handler::Sync(func), vec![method]);
+        self
+    }
+
     /// Register an HTTP handler that will respond to all methods that are not handled explicitly by
     /// other handlers.
     pub fn or_else_any_method(mut self, pattern: &str, func: HandlerFn<D>) -> Self {
@@ -284,8 +301,8 @@ impl<'a, D: 'a> Router<'a, D> {
         self
     }
 
-    /// Register an HTTP handler that will respond to any requests. Enables the use of `async/await`
-    /// syntax in the callback.
+    /// Register an HTTP handler that will respond to any requests with a standard method. Enables
+    /// the use of `async/await` syntax in the callback.
     pub fn on_async<T>(mut self, pattern: &str, func: fn(Request, RouteContext<D>) -> T) -> Self
     where
         T: Future<Output = Result<Response>> + 'a,
@@ -298,6 +315,26 @@ impl<'a, D: 'a> Router<'a, D> {
         self
     }
 
+    /// Register an HTTP handler that will exclusively respond to requests with the given method,
+    /// such as an extension method like `Method::from("PURGE")`. Enables the use of `async/await`
+    /// syntax in the callback.
+    pub fn on_method_async<T>(
+        mut self,
+        method: Method,
+        pattern: &str,
+        func: fn(Request, RouteContext<D>) -> T,
+    ) -> Self
+    where
+        T: Future<Output = Result<Response>> + 'a,
+    {
+        self.add_handler(
+            pattern,
+            Handler::Async(Rc::new(move |req, route| Box::pin(func(req, route)))),
+            vec![method],
+        );
+        self
+    }
+
     /// Register an HTTP handler that will respond to all methods that are not handled explicitly by
     /// other handlers. Enables the use of `async/await` syntax in the callback.
     pub fn or_else_any_method_async<T>(
@@ -333,7 +370,10 @@ impl<'a, D: 'a> Router<'a, D> {
     }
 
     /// Handle the request provided to the `Router` and return a `Future`.
-    pub async fn run(self, req: Request, env: Env) -> Result<Response> {
+    pub async fn run(self, mut req: Request, env: Env) -> Result<Response> {
+        if let Some(limit) = self.body_limit {
+            req.set_body_limit(limit);
+        }
         let (handlers, data, or_else_any_method_handler) = self.split();
 
         if let Some(handlers) = handlers.get(&req.method()) {
@@ -343,21 +383,19 @@ impl<'a, D: 'a> Router<'a, D> {
                     env,
                     params: params.into(),
                 };
-                return match value {
+                return payload_too_large(match value {
                     Handler::Sync(func) => (func)(req, route_info),
                     Handler::Async(func) => (func)(req, route_info).await,
-                };
+                });
             }
         }
 
-        for method in Method::all() {
-            if method == Method::Head || method == Method::Options || method == Method::Trace {
+        for (method, handlers) in handlers.iter() {
+            if *method == Method::Head || *method == Method::Options || *method == Method::Trace {
                 continue;
             }
-            if let Some(handlers) = handlers.get(&method) {
-                if let Ok(Match { .. }) = handlers.at(&req.path()) {
-                    return Response::error("Method Not Allowed", 405);
-                }
+            if let Ok(Match { .. }) = handlers.at(&req.path()) {
+                return Response::error("Method Not Allowed", 405);
             }
         }
 
@@ -367,16 +405,24 @@ impl<'a, D: 'a> Router<'a, D> {
                 env,
                 params: params.into(),
             };
-            return match value {
+            return payload_too_large(match value {
                 Handler::Sync(func) => (func)(req, route_info),
                 Handler::Async(func) => (func)(req, route_info).await,
-            };
+            });
         }
 
         Response::error("Not Found", 404)
     }
 }
 
+/// Responds to requests whose body exceeded their limit with a 413 status code.
+fn payload_too_large(result: Result<Response>) -> Result<Response> {
+    match result {
+        Err(Error::BodyTooLarge(_)) => Response::error("Payload Too Large", 413),
+        result => result,
+    }
+}
+
 type NodeWithHandlers<'a, D> = Node<Handler<'a, D>>;
 
 impl<'a, D: 'a> Router<'a, D> {
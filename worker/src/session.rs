@@ -0,0 +1,370 @@
+use js_sys::{Array, JsString, Object as JsObject, Uint8Array};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{CryptoKey, SubtleCrypto, WorkerGlobalScope};
+
+use crate::{r2::js_object, Cookie, Date, Env, Error, Request, Result, SameSite};
+
+/// The length of the random initialization vector prepended to encrypted sessions.
+const IV_LENGTH: usize = 12;
+
+/// The HKDF info of the key signing sessions, derived from a session key.
+const SIGNING_INFO: &str = "worker session signing";
+
+/// The HKDF info of the key encrypting sessions, derived from a session key.
+const ENCRYPTION_INFO: &str = "worker session encryption";
+
+/// Stateless sessions, storing a serializable value in a cookie signed with HMAC-SHA256 and
+/// optionally encrypted with AES-GCM, so that clients can neither forge nor (when encrypted) read
+/// their session.
+///
+/// Separate signing and encryption keys are derived from each session key with HKDF-SHA256. The
+/// signature also covers the cookie name, so a value sealed for one session cookie is rejected
+/// under another name.
+///
+/// Keys can be rotated by adding the previous keys with
+/// [with_previous_key](Self::with_previous_key): sessions are always signed with the current key,
+/// but are still accepted when signed with a previous one. Sessions that fail verification or
+/// have expired are treated as missing.
+///
+/// ```no_run
+/// #[derive(Serialize, Deserialize)]
+/// struct User {
+///     id: u64,
+/// }
+///
+/// let session = Session::from_secrets(&env, "session", &["SESSION_KEY", "OLD_SESSION_KEY"])?
+///     .with_encryption(true)
+///     .with_max_age(24 * 60 * 60);
+///
+/// match session.load::<User>(&req).await? {
+///     Some(user) => Response::ok(format!("Welcome back, user {}", user.id)),
+///     None => Response::ok("Welcome!")?.with_cookie(&session.cookie(&User { id: 42 }).await?),
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Session {
+    cookie_name: String,
+    keys: Vec<String>,
+    encrypted: bool,
+    max_age: Option<u64>,
+    path: String,
+    domain: Option<String>,
+    same_site: SameSite,
+    secure: bool,
+}
+
+#[derive(Serialize)]
+struct SealedPayload<'a, T> {
+    value: &'a T,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct OpenedPayload<T> {
+    value: T,
+    expires: Option<u64>,
+}
+
+impl Session {
+    /// Creates sessions stored in the cookie with the given name, signed with `key`. The cookie is
+    /// set on the `/` path, with the `Secure`, `HttpOnly` and `SameSite=Lax` attributes.
+    pub fn new(cookie_name: impl Into<String>, key: impl Into<String>) -> Self {
+        Self {
+            cookie_name: cookie_name.into(),
+            keys: vec![key.into()],
+            encrypted: false,
+            max_age: None,
+            path: "/".into(),
+            domain: None,
+            same_site: SameSite::Lax,
+            secure: true,
+        }
+    }
+
+    /// Creates sessions with keys read from the secrets with the given bindings. The first secret
+    /// is the current key, and the others are previous keys.
+    pub fn from_secrets(
+        env: &Env,
+        cookie_name: impl Into<String>,
+        bindings: &[&str],
+    ) -> Result<Self> {
+        let (current, previous) = bindings
+            .split_first()
+            .ok_or_else(|| Error::RustError("sessions require at least one key".into()))?;
+
+        let mut session = Self::new(cookie_name, env.secret(current)?.to_string());
+        for binding in previous {
+            session = session.with_previous_key(env.secret(binding)?.to_string());
+        }
+        Ok(session)
+    }
+
+    /// Adds a previous key, with which sessions are still accepted but no longer signed.
+    pub fn with_previous_key(mut self, key: impl Into<String>) -> Self {
+        self.keys.push(key.into());
+        self
+    }
+
+    /// Sets whether the session value is encrypted, rather than only signed. Defaults to `false`.
+    pub fn with_encryption(mut self, encrypted: bool) -> Self {
+        self.encrypted = encrypted;
+        self
+    }
+
+    /// Sets the number of seconds after which sessions expire, both on the client and when
+    /// loaded. By default, sessions last until the client discards the cookie.
+    pub fn with_max_age(mut self, seconds: u64) -> Self {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    /// Sets the `Path` attribute of the session cookie. Defaults to `/`.
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Sets the `Domain` attribute of the session cookie.
+    pub fn with_domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Sets the `SameSite` attribute of the session cookie. Defaults to [SameSite::Lax].
+    pub fn with_same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = same_site;
+        self
+    }
+
+    /// Sets the `Secure` attribute of the session cookie. Defaults to `true`.
+    pub fn with_secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Loads the session sent with the request, if it has one that is valid and not expired.
+    pub async fn load<T: DeserializeOwned>(&self, req: &Request) -> Result<Option<T>> {
+        match req.cookie(&self.cookie_name)? {
+            Some(value) => self.open(&value).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Creates the cookie storing `value` as the session, to be set on a response with
+    /// [Response::with_cookie](crate::Response::with_cookie).
+    pub async fn cookie<T: Serialize>(&self, value: &T) -> Result<Cookie> {
+        let value = self.seal(value).await?;
+        let mut cookie = self.base_cookie(value)?;
+        if let Some(max_age) = self.max_age {
+            cookie = cookie.with_max_age(max_age as i64);
+        }
+        Ok(cookie)
+    }
+
+    /// Creates the cookie removing the session from the client.
+    pub fn removal_cookie(&self) -> Result<Cookie> {
+        Ok(self.base_cookie(String::new())?.with_max_age(0))
+    }
+
+    fn base_cookie(&self, value: String) -> Result<Cookie> {
+        let cookie = Cookie::new(&self.cookie_name, value)?
+            .with_path(&self.path)?
+            .with_same_site(self.same_site)
+            .with_secure(self.secure)
+            .with_http_only(true);
+        match &self.domain {
+            Some(domain) => cookie.with_domain(domain),
+            None => Ok(cookie),
+        }
+    }
+
+    async fn seal<T: Serialize>(&self, value: &T) -> Result<String> {
+        let payload = serde_json::to_vec(&SealedPayload {
+            value,
+            expires: self
+                .max_age
+                .map(|max_age| Date::now().as_millis() + max_age * 1000),
+        })?;
+
+        let key = &self.keys[0];
+        let data = if self.encrypted {
+            encrypt(key, &payload).await?
+        } else {
+            payload
+        };
+
+        let data = base64::encode_config(data, base64::URL_SAFE_NO_PAD);
+        let signature = sign(key, &self.signed_data(&data)).await?;
+        Ok(format!(
+            "{data}.{}",
+            base64::encode_config(signature, base64::URL_SAFE_NO_PAD)
+        ))
+    }
+
+    async fn open<T: DeserializeOwned>(&self, sealed: &str) -> Result<Option<T>> {
+        let (data, signature) = match sealed.rsplit_once('.') {
+            Some(parts) => parts,
+            None => return Ok(None),
+        };
+        let signature = match base64::decode_config(signature, base64::URL_SAFE_NO_PAD) {
+            Ok(signature) => signature,
+            Err(_) => return Ok(None),
+        };
+
+        for key in &self.keys {
+            if !verify(key, &signature, &self.signed_data(data)).await? {
+                continue;
+            }
+
+            let data = match base64::decode_config(data, base64::URL_SAFE_NO_PAD) {
+                Ok(data) => data,
+                Err(_) => return Ok(None),
+            };
+            let payload = if self.encrypted {
+                match decrypt(key, &data).await {
+                    Ok(payload) => payload,
+                    Err(_) => return Ok(None),
+                }
+            } else {
+                data
+            };
+
+            let payload: OpenedPayload<T> = match serde_json::from_slice(&payload) {
+                Ok(payload) => payload,
+                Err(_) => return Ok(None),
+            };
+            return match payload.expires {
+                Some(expires) if expires <= Date::now().as_millis() => Ok(None),
+                _ => Ok(Some(payload.value)),
+            };
+        }
+
+        Ok(None)
+    }
+
+    /// The data covered by the signature of a session: its encoded data, prefixed by the cookie
+    /// name, which can't contain `=`.
+    fn signed_data(&self, data: &str) -> Vec<u8> {
+        format!("{}={}", self.cookie_name, data).into_bytes()
+    }
+}
+
+fn subtle() -> Result<SubtleCrypto> {
+    let global: WorkerGlobalScope = js_sys::global().unchecked_into();
+    Ok(global.crypto()?.subtle())
+}
+
+async fn import_key(key_data: &[u8], algorithm: &JsObject, usages: &[&str]) -> Result<CryptoKey> {
+    let usages: Array = usages
+        .iter()
+        .map(|usage| JsValue::from_str(usage))
+        .collect();
+    let promise = subtle()?.import_key_with_object(
+        "raw",
+        &Uint8Array::from(key_data),
+        algorithm,
+        false,
+        &usages,
+    )?;
+    Ok(JsFuture::from(promise).await?.unchecked_into())
+}
+
+/// Derives a 256-bit key from `key` with HKDF-SHA256, using `info` to derive distinct keys for
+/// distinct purposes.
+async fn derive_key(key: &str, info: &str) -> Result<Vec<u8>> {
+    let algorithm = js_object! {
+        "name" => "HKDF",
+    };
+    let base_key = import_key(key.as_bytes(), &algorithm, &["deriveBits"]).await?;
+
+    let algorithm = js_object! {
+        "name" => "HKDF",
+        "hash" => "SHA-256",
+        "salt" => Uint8Array::new_with_length(0),
+        "info" => Uint8Array::from(info.as_bytes()),
+    };
+    let promise = subtle()?.derive_bits_with_object(&algorithm, &base_key, 256)?;
+    Ok(Uint8Array::new(&JsFuture::from(promise).await?).to_vec())
+}
+
+async fn hmac_key(key: &str) -> Result<CryptoKey> {
+    let algorithm = js_object! {
+        "name" => "HMAC",
+        "hash" => "SHA-256",
+    };
+    let key_data = derive_key(key, SIGNING_INFO).await?;
+    import_key(&key_data, &algorithm, &["sign", "verify"]).await
+}
+
+async fn aes_key(key: &str) -> Result<CryptoKey> {
+    let algorithm = js_object! {
+        "name" => "AES-GCM",
+    };
+    let key_data = derive_key(key, ENCRYPTION_INFO).await?;
+    import_key(&key_data, &algorithm, &["encrypt", "decrypt"]).await
+}
+
+async fn sign(key: &str, data: &[u8]) -> Result<Vec<u8>> {
+    let algorithm = js_object! {
+        "name" => "HMAC",
+    };
+    let promise = subtle()?.sign_with_object_and_buffer_source(
+        &algorithm,
+        &hmac_key(key).await?,
+        &Uint8Array::from(data),
+    )?;
+    Ok(Uint8Array::new(&JsFuture::from(promise).await?).to_vec())
+}
+
+async fn verify(key: &str, signature: &[u8], data: &[u8]) -> Result<bool> {
+    let algorithm = js_object! {
+        "name" => "HMAC",
+    };
+    let promise = subtle()?.verify_with_object_and_buffer_source_and_buffer_source(
+        &algorithm,
+        &hmac_key(key).await?,
+        &Uint8Array::from(signature),
+        &Uint8Array::from(data),
+    )?;
+    Ok(JsFuture::from(promise).await?.is_truthy())
+}
+
+async fn encrypt(key: &str, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let global: WorkerGlobalScope = js_sys::global().unchecked_into();
+    let mut iv = [0; IV_LENGTH];
+    global.crypto()?.get_random_values_with_u8_array(&mut iv)?;
+
+    let algorithm = js_object! {
+        "name" => "AES-GCM",
+        "iv" => Uint8Array::from(&iv[..]),
+    };
+    let promise = subtle()?.encrypt_with_object_and_buffer_source(
+        &algorithm,
+        &aes_key(key).await?,
+        &Uint8Array::from(plaintext),
+    )?;
+    let ciphertext = Uint8Array::new(&JsFuture::from(promise).await?).to_vec();
+
+    Ok([&iv[..], &ciphertext].concat())
+}
+
+async fn decrypt(key: &str, data: &[u8]) -> Result<Vec<u8>> {
+    if data.len() < IV_LENGTH {
+        return Err(Error::RustError("encrypted session is too short".into()));
+    }
+    let (iv, ciphertext) = data.split_at(IV_LENGTH);
+
+    let algorithm = js_object! {
+        "name" => "AES-GCM",
+        "iv" => Uint8Array::from(iv),
+    };
+    let promise = subtle()?.decrypt_with_object_and_buffer_source(
+        &algorithm,
+        &aes_key(key).await?,
+        &Uint8Array::from(ciphertext),
+    )?;
+    Ok(Uint8Array::new(&JsFuture::from(promise).await?).to_vec())
+}
@@ -0,0 +1,214 @@
+//! [Server-sent events](https://developer.mozilla.org/en-US/docs/Web/API/Server-sent_events),
+//! streamed to clients by a [Response](crate::Response) created with
+//! [Response::from_sse](crate::Response::from_sse).
+//!
+//! ```no_run
+//! let events = futures_util::stream::iter(1..=3).map(|progress| {
+//!     Event::json(&serde_json::json!({ "progress": progress }))
+//!         .map(|event| event.with_event("progress"))
+//! });
+//! let stream = SseStream::new(events).with_keep_alive(Duration::from_secs(15));
+//! Response::from_sse(stream)
+//! ```
+
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_util::{Stream, TryStream};
+use pin_project::pin_project;
+use serde::Serialize;
+
+use crate::{Delay, Error, Result};
+
+/// A server-sent event, formatted in the `text/event-stream` format by its
+/// [Display](fmt::Display) implementation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Event {
+    event: Option<String>,
+    id: Option<String>,
+    retry: Option<Duration>,
+    data: Option<String>,
+    comment: Option<String>,
+}
+
+impl Event {
+    /// Creates a message event with the given data, which may span multiple lines.
+    pub fn data(data: impl Into<String>) -> Self {
+        Self::default().with_data(data)
+    }
+
+    /// Creates a message event with `value` encoded as JSON as its data.
+    pub fn json<T: Serialize>(value: &T) -> Result<Self> {
+        Ok(Self::data(serde_json::to_string(value)?))
+    }
+
+    /// Creates a comment, which is ignored by clients but keeps the connection alive.
+    pub fn comment(comment: impl Into<String>) -> Self {
+        Self {
+            comment: Some(comment.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the data of the event, which may span multiple lines.
+    pub fn with_data(mut self, data: impl Into<String>) -> Self {
+        self.data = Some(data.into());
+        self
+    }
+
+    /// Sets the name of the event, dispatched to the listeners of that name rather than to
+    /// `onmessage` on the client.
+    pub fn with_event(mut self, event: impl Into<String>) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+
+    /// Sets the id of the event, which the client sends back in the `Last-Event-ID` header when
+    /// reconnecting.
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Sets how long the client waits before reconnecting when the connection is lost.
+    pub fn with_retry(mut self, retry: Duration) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+}
+
+fn write_field(f: &mut fmt::Formatter<'_>, name: &str, value: &str) -> fmt::Result {
+    // Line breaks would end the field early, so values spanning multiple lines are written as
+    // one field per line, which clients join back with `\n`.
+    for line in value
+        .split("\r\n")
+        .flat_map(|line| line.split(['\r', '\n']))
+    {
+        writeln!(f, "{name}: {line}")?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(comment) = &self.comment {
+            write_field(f, "", comment)?;
+        }
+        if let Some(event) = &self.event {
+            write_field(f, "event", event)?;
+        }
+        if let Some(id) = &self.id {
+            write_field(f, "id", id)?;
+        }
+        if let Some(retry) = self.retry {
+            writeln!(f, "retry: {}", retry.as_millis())?;
+        }
+        if let Some(data) = &self.data {
+            write_field(f, "data", data)?;
+        }
+        writeln!(f)
+    }
+}
+
+/// A stream of server-sent events, which can send comments to keep the connection alive while
+/// no events are sent. It can be returned to clients with
+/// [Response::from_sse](crate::Response::from_sse).
+#[pin_project]
+pub struct SseStream<S> {
+    #[pin]
+    events: S,
+    keep_alive: Option<KeepAlive>,
+}
+
+struct KeepAlive {
+    interval: Duration,
+    delay: Delay,
+    idle: bool,
+}
+
+impl<S> SseStream<S>
+where
+    S: TryStream<Ok = Event>,
+    S::Error: Into<Error>,
+{
+    /// Creates a stream of the given events, without keep-alive comments.
+    pub fn new(events: S) -> Self {
+        Self {
+            events,
+            keep_alive: None,
+        }
+    }
+
+    /// Sends an empty comment when no events were sent for at least `interval`, which prevents
+    /// clients and proxies from closing idle connections.
+    pub fn with_keep_alive(mut self, interval: Duration) -> Self {
+        self.keep_alive = Some(KeepAlive {
+            interval,
+            delay: Delay::from(interval),
+            idle: true,
+        });
+        self
+    }
+}
+
+impl<S> Stream for SseStream<S>
+where
+    S: TryStream<Ok = Event>,
+    S::Error: Into<Error>,
+{
+    type Item = Result<Event>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        match this.events.try_poll_next(cx) {
+            Poll::Ready(Some(event)) => {
+                if let Some(keep_alive) = this.keep_alive {
+                    keep_alive.idle = false;
+                }
+                return Poll::Ready(Some(event.map_err(Into::into)));
+            }
+            Poll::Ready(None) => return Poll::Ready(None),
+            Poll::Pending => {}
+        }
+
+        let keep_alive = match this.keep_alive {
+            Some(keep_alive) => keep_alive,
+            None => return Poll::Pending,
+        };
+
+        // Rather than restarting the delay for every event, a comment is only sent if no events
+        // were sent during a whole interval.
+        while Pin::new(&mut keep_alive.delay).poll(cx).is_ready() {
+            keep_alive.delay = Delay::from(keep_alive.interval);
+            if keep_alive.idle {
+                return Poll::Ready(Some(Ok(Event::comment(""))));
+            }
+            keep_alive.idle = true;
+        }
+
+        Poll::Pending
+    }
+}
+
+#[test]
+fn formats_events() {
+    let event = Event::data("first\nsecond\r\nthird")
+        .with_event("update")
+        .with_id("7")
+        .with_retry(Duration::from_secs(3));
+    assert_eq!(
+        event.to_string(),
+        "event: update\nid: 7\nretry: 3000\ndata: first\ndata: second\ndata: third\n\n"
+    );
+    assert_eq!(Event::comment("").to_string(), ": \n\n");
+    assert_eq!(
+        Event::json(&vec![1, 2]).unwrap().to_string(),
+        "data: [1,2]\n\n"
+    );
+}
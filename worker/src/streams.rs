@@ -1,9 +1,10 @@
 use std::{
+    io,
     pin::Pin,
     task::{Context, Poll},
 };
 
-use futures_util::{Stream, TryStreamExt};
+use futures_util::{AsyncBufRead, AsyncRead, Stream, TryStream, TryStreamExt};
 use js_sys::{BigInt, Uint8Array};
 use pin_project::pin_project;
 use wasm_bindgen::{JsCast, JsValue};
@@ -38,6 +39,94 @@ impl Stream for ByteStream {
     }
 }
 
+impl ByteStream {
+    /// Adapts the stream into an [AsyncRead] and [AsyncBufRead], to be consumed by IO utilities
+    /// such as decompressors and parsers without collecting it into a `Vec<u8>` first.
+    pub fn into_async_read(self) -> ByteStreamReader {
+        ByteStreamReader {
+            stream: self,
+            chunk: Vec::new(),
+            position: 0,
+            done: false,
+        }
+    }
+}
+
+/// Reads the bytes of a [ByteStream] through [AsyncRead] and [AsyncBufRead]. Refer to
+/// [ByteStream::into_async_read].
+#[pin_project]
+#[derive(Debug)]
+pub struct ByteStreamReader {
+    #[pin]
+    stream: ByteStream,
+    chunk: Vec<u8>,
+    position: usize,
+    done: bool,
+}
+
+impl AsyncBufRead for ByteStreamReader {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let mut this = self.project();
+        while *this.position == this.chunk.len() && !*this.done {
+            match futures_util::ready!(this.stream.as_mut().poll_next(cx)) {
+                Some(Ok(chunk)) => {
+                    *this.chunk = chunk;
+                    *this.position = 0;
+                }
+                Some(Err(e)) => {
+                    return Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, e.to_string())))
+                }
+                None => *this.done = true,
+            }
+        }
+
+        Poll::Ready(Ok(&this.chunk[*this.position..]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.project();
+        *this.position = (*this.position + amt).min(this.chunk.len());
+    }
+}
+
+impl AsyncRead for ByteStreamReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let available = futures_util::ready!(self.as_mut().poll_fill_buf(cx))?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+
+        Poll::Ready(Ok(len))
+    }
+}
+
+/// Converts a stream of byte chunks into a JavaScript `ReadableStream`, which can be used as the
+/// body of a request or response.
+pub(crate) fn to_readable_stream<S>(stream: S) -> ReadableStream
+where
+    S: TryStream + 'static,
+    S::Ok: Into<Vec<u8>>,
+    S::Error: Into<Error>,
+{
+    let js_stream = stream
+        .map_ok(|item| -> Vec<u8> { item.into() })
+        .map_ok(|chunk| {
+            let array = Uint8Array::new_with_length(chunk.len() as _);
+            array.copy_from(&chunk);
+
+            array.into()
+        })
+        .map_err(|err| -> crate::Error { err.into() })
+        .map_err(|e| JsValue::from(e.to_string()));
+
+    let stream = wasm_streams::ReadableStream::from_stream(js_stream);
+    stream.into_raw().dyn_into().unwrap()
+}
+
 #[pin_project]
 pub struct FixedLengthStream {
     length: u64,
@@ -0,0 +1,14 @@
+//! Test doubles for runtime bindings, allowing Worker code to be exercised from
+//! `wasm-bindgen-test`, or from native `#[test]`s for bindings abstracted behind a trait, without
+//! a running `wrangler dev`.
+//!
+//! This module is only compiled with the `testing` feature, which is meant to be enabled in
+//! `[dev-dependencies]` rather than in production builds.
+
+#[cfg(feature = "queue")]
+mod queue;
+mod r2;
+
+#[cfg(feature = "queue")]
+pub use queue::*;
+pub use r2::*;
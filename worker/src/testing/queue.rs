@@ -0,0 +1,281 @@
+use std::{cell::RefCell, rc::Rc};
+
+use js_sys::{Array, Object, Reflect};
+use serde::{de::DeserializeOwned, Serialize};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use worker_sys::MessageBatch as MessageBatchSys;
+
+use crate::{Date, Error, Queue, Result};
+
+/// What a consumer did with a message of a batch built by [`MessageBatchBuilder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessageOutcome {
+    /// The message was neither acknowledged nor retried.
+    Unhandled,
+    /// The message was acknowledged, individually or through `ack_all`.
+    Acked,
+    /// The message was marked for retry, individually or through `retry_all`.
+    Retried { delay_seconds: Option<u32> },
+}
+
+#[derive(Debug, Default)]
+struct BatchState {
+    ids: Vec<String>,
+    messages: Vec<Option<MessageOutcome>>,
+    batch: Option<MessageOutcome>,
+}
+
+/// Builds a synthetic message batch that can be handed to a queue consumer, recording which
+/// messages the consumer acknowledged or retried.
+///
+/// ```no_run
+/// let (batch, outcome) = MessageBatchBuilder::new("my_queue")
+///     .message(&MyType { foo: 1 })?
+///     .message_with("poison", Date::now(), 3, &MyType { foo: 2 })?
+///     .build();
+///
+/// my_consumer(MessageBatch::from(batch)).await?;
+///
+/// assert_eq!(outcome.outcome("poison"), Some(MessageOutcome::Acked));
+/// ```
+pub struct MessageBatchBuilder {
+    queue: String,
+    messages: Array,
+    state: Rc<RefCell<BatchState>>,
+}
+
+impl MessageBatchBuilder {
+    /// Start building a batch delivered from the Queue named `queue`.
+    pub fn new(queue: &str) -> Self {
+        Self {
+            queue: queue.to_string(),
+            messages: Array::new(),
+            state: Rc::default(),
+        }
+    }
+
+    /// Add a message with the given body, an id equal to its position in the batch, the current
+    /// time as timestamp and a single delivery attempt.
+    pub fn message<T: Serialize>(self, body: &T) -> Result<Self> {
+        let id = self.messages.length().to_string();
+        self.message_with(&id, Date::now(), 1, body)
+    }
+
+    /// Add a message with the given id, timestamp, number of delivery attempts and body.
+    pub fn message_with<T: Serialize>(
+        self,
+        id: &str,
+        timestamp: Date,
+        attempts: u32,
+        body: &T,
+    ) -> Result<Self> {
+        self.push(id, timestamp, attempts, serde_wasm_bindgen::to_value(body)?);
+        Ok(self)
+    }
+
+    fn push(&self, id: &str, timestamp: Date, attempts: u32, body: JsValue) {
+        let index = {
+            let mut state = self.state.borrow_mut();
+            state.ids.push(id.to_string());
+            state.messages.push(None);
+            state.messages.len() - 1
+        };
+
+        let state = self.state.clone();
+        let ack = Closure::<dyn FnMut()>::new(move || {
+            state.borrow_mut().messages[index] = Some(MessageOutcome::Acked);
+        });
+
+        let state = self.state.clone();
+        let retry = Closure::<dyn FnMut(JsValue)>::new(move |options: JsValue| {
+            state.borrow_mut().messages[index] = Some(retried(&options));
+        });
+
+        let message = Object::new();
+        set(&message, "id", &id.into());
+        set(&message, "timestamp", &js_sys::Date::from(timestamp).into());
+        set(&message, "attempts", &attempts.into());
+        set(&message, "body", &body);
+        set(&message, "ack", &ack.into_js_value());
+        set(&message, "retry", &retry.into_js_value());
+
+        self.messages.push(&message);
+    }
+
+    /// Finish the batch, returning it along with a handle to inspect what was done with each of
+    /// its messages.
+    pub fn build(self) -> (MessageBatchSys, BatchOutcome) {
+        let state = self.state.clone();
+        let ack_all = Closure::<dyn FnMut()>::new(move || {
+            state.borrow_mut().batch = Some(MessageOutcome::Acked);
+        });
+
+        let state = self.state.clone();
+        let retry_all = Closure::<dyn FnMut(JsValue)>::new(move |options: JsValue| {
+            state.borrow_mut().batch = Some(retried(&options));
+        });
+
+        let batch = Object::new();
+        set(&batch, "queue", &self.queue.into());
+        set(&batch, "messages", &self.messages);
+        set(&batch, "ackAll", &ack_all.into_js_value());
+        set(&batch, "retryAll", &retry_all.into_js_value());
+
+        (batch.unchecked_into(), BatchOutcome { state: self.state })
+    }
+}
+
+fn set(target: &Object, key: &str, value: &JsValue) {
+    Reflect::set(target, &key.into(), value).expect("failed to set property on a plain object");
+}
+
+fn retried(options: &JsValue) -> MessageOutcome {
+    let delay_seconds = if options.is_object() {
+        Reflect::get(options, &"delaySeconds".into())
+            .ok()
+            .and_then(|delay| delay.as_f64())
+            .map(|delay| delay as u32)
+    } else {
+        None
+    };
+
+    MessageOutcome::Retried { delay_seconds }
+}
+
+/// The messages acknowledged or retried by a consumer of a batch built by a
+/// [`MessageBatchBuilder`]. An explicit `ack` or `retry` of a message takes precedence over
+/// `ack_all` and `retry_all` on the batch.
+pub struct BatchOutcome {
+    state: Rc<RefCell<BatchState>>,
+}
+
+impl BatchOutcome {
+    /// What was done with the message of the given id, or `None` if the batch has no such message.
+    pub fn outcome(&self, id: &str) -> Option<MessageOutcome> {
+        let state = self.state.borrow();
+        let index = state.ids.iter().position(|i| i == id)?;
+        Some(Self::resolve(&state, index))
+    }
+
+    /// The ids of every acknowledged message, in the order they were added to the batch.
+    pub fn acked(&self) -> Vec<String> {
+        self.matching(|outcome| outcome == &MessageOutcome::Acked)
+    }
+
+    /// The ids of every message marked for retry, in the order they were added to the batch.
+    pub fn retried(&self) -> Vec<String> {
+        self.matching(|outcome| matches!(outcome, MessageOutcome::Retried { .. }))
+    }
+
+    /// The ids of every message that was neither acknowledged nor retried.
+    pub fn unhandled(&self) -> Vec<String> {
+        self.matching(|outcome| outcome == &MessageOutcome::Unhandled)
+    }
+
+    fn matching(&self, predicate: impl Fn(&MessageOutcome) -> bool) -> Vec<String> {
+        let state = self.state.borrow();
+        state
+            .ids
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| predicate(&Self::resolve(&state, *index)))
+            .map(|(_, id)| id.clone())
+            .collect()
+    }
+
+    fn resolve(state: &BatchState, index: usize) -> MessageOutcome {
+        state.messages[index]
+            .clone()
+            .or_else(|| state.batch.clone())
+            .unwrap_or(MessageOutcome::Unhandled)
+    }
+}
+
+/// An in-memory stand-in for a Queue binding, recording every message sent through it.
+///
+/// ```no_run
+/// let memory = MemoryQueue::new();
+/// produce(&memory.queue()).await?;
+///
+/// let sent: Vec<MyType> = memory.messages()?;
+/// let (batch, outcome) = memory.drain_batch("my_queue").build();
+/// ```
+#[derive(Clone)]
+pub struct MemoryQueue {
+    queue: JsValue,
+    messages: Rc<RefCell<Vec<JsValue>>>,
+}
+
+impl Default for MemoryQueue {
+    fn default() -> Self {
+        let messages: Rc<RefCell<Vec<JsValue>>> = Rc::default();
+        let queue = Object::new();
+
+        let sent = messages.clone();
+        let send = Closure::<dyn FnMut(JsValue) -> js_sys::Promise>::new(move |body: JsValue| {
+            sent.borrow_mut().push(body);
+            js_sys::Promise::resolve(&JsValue::UNDEFINED)
+        });
+
+        let sent = messages.clone();
+        let send_batch =
+            Closure::<dyn FnMut(JsValue) -> js_sys::Promise>::new(move |batch: JsValue| {
+                let mut sent = sent.borrow_mut();
+                for request in Array::from(&batch).iter() {
+                    match Reflect::get(&request, &"body".into()) {
+                        Ok(body) => sent.push(body),
+                        Err(e) => return js_sys::Promise::reject(&e),
+                    }
+                }
+                js_sys::Promise::resolve(&JsValue::UNDEFINED)
+            });
+        set(&queue, "send", &send.into_js_value());
+        set(&queue, "sendBatch", &send_batch.into_js_value());
+
+        Self {
+            queue: queue.into(),
+            messages,
+        }
+    }
+}
+
+impl MemoryQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A [`Queue`] that stores the messages sent to it in this `MemoryQueue`.
+    pub fn queue(&self) -> Queue {
+        self.queue.clone().unchecked_into()
+    }
+
+    /// The number of messages sent so far.
+    pub fn len(&self) -> usize {
+        self.messages.borrow().len()
+    }
+
+    /// Whether no message has been sent so far.
+    pub fn is_empty(&self) -> bool {
+        self.messages.borrow().is_empty()
+    }
+
+    /// Deserializes every message sent so far, in the order they were sent.
+    pub fn messages<T: DeserializeOwned>(&self) -> Result<Vec<T>> {
+        self.messages
+            .borrow()
+            .iter()
+            .map(|body| serde_wasm_bindgen::from_value(body.clone()).map_err(Error::from))
+            .collect()
+    }
+
+    /// Drains the messages sent so far into a [`MessageBatchBuilder`] for the Queue named
+    /// `queue`, so they can be delivered to a consumer.
+    pub fn drain_batch(&self, queue: &str) -> MessageBatchBuilder {
+        let builder = MessageBatchBuilder::new(queue);
+        for body in self.messages.borrow_mut().drain(..) {
+            let id = builder.messages.length().to_string();
+            builder.push(&id, Date::now(), 1, body);
+        }
+        builder
+    }
+}
@@ -0,0 +1,577 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    rc::Rc,
+};
+
+use async_trait::async_trait;
+use futures_util::TryStreamExt;
+
+use crate::{
+    BucketStore, Conditional, CreateMultipartUploadOptions, Data, Error, GetObjectOptions,
+    HttpMetadata, Include, ListObjectsOptions, ObjectInfo, ObjectList, PartInfo, PutObjectOptions,
+    Range, Result, StorageClass, StoredBody, StoredObject,
+};
+
+/// An in-memory [BucketStore], mirroring the semantics of R2 for conditional and ranged reads,
+/// conditional writes, listing and multipart uploads.
+///
+/// Unlike a [Bucket](crate::Bucket), it can be used outside of a JavaScript runtime, in plain
+/// `#[test]`s. Clones share the same objects.
+///
+/// ```no_run
+/// let bucket = MemoryBucket::new();
+/// bucket.insert("config.json", r#"{"enabled":true}"#);
+/// assert!(load_config(&bucket).await?.enabled);
+/// ```
+#[derive(Clone, Default)]
+pub struct MemoryBucket {
+    state: Rc<RefCell<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    objects: BTreeMap<String, StoredEntry>,
+    uploads: HashMap<String, Upload>,
+    /// Incremented on every write, to derive unique versions, etags and upload ids.
+    counter: u64,
+}
+
+#[derive(Clone)]
+struct StoredEntry {
+    info: ObjectInfo,
+    body: Vec<u8>,
+}
+
+struct Upload {
+    key: String,
+    http_metadata: HttpMetadata,
+    custom_metadata: HashMap<String, String>,
+    parts: BTreeMap<u16, (String, Vec<u8>)>,
+}
+
+impl MemoryBucket {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores `value` under `key` without any metadata, replacing any existing object.
+    pub fn insert(&self, key: impl Into<String>, value: impl Into<Vec<u8>>) {
+        let mut state = self.state.borrow_mut();
+        let entry = state.entry(
+            key.into(),
+            value.into(),
+            HttpMetadata::default(),
+            HashMap::new(),
+            StorageClass::Standard,
+        );
+        state.objects.insert(entry.info.key.clone(), entry);
+    }
+
+    /// The keys of every stored object, in lexicographic order.
+    pub fn keys(&self) -> Vec<String> {
+        self.state.borrow().objects.keys().cloned().collect()
+    }
+
+    /// The body of the object stored under `key`, if any.
+    pub fn bytes(&self, key: &str) -> Option<Vec<u8>> {
+        self.state
+            .borrow()
+            .objects
+            .get(key)
+            .map(|entry| entry.body.clone())
+    }
+}
+
+impl State {
+    fn next(&mut self) -> u64 {
+        self.counter += 1;
+        self.counter
+    }
+
+    fn entry(
+        &mut self,
+        key: String,
+        body: Vec<u8>,
+        http_metadata: HttpMetadata,
+        custom_metadata: HashMap<String, String>,
+        storage_class: StorageClass,
+    ) -> StoredEntry {
+        let version = self.next();
+        let etag = format!("{version:032x}");
+        StoredEntry {
+            info: ObjectInfo {
+                key,
+                version: version.to_string(),
+                size: body.len() as u64,
+                http_etag: format!("\"{etag}\""),
+                etag,
+                uploaded_millis: now_millis(),
+                http_metadata,
+                custom_metadata,
+                range: None,
+                storage_class,
+            },
+            body,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl BucketStore for MemoryBucket {
+    async fn head(&self, key: &str) -> Result<Option<ObjectInfo>> {
+        Ok(self
+            .state
+            .borrow()
+            .objects
+            .get(key)
+            .map(|entry| entry.info.clone()))
+    }
+
+    async fn get(&self, key: &str, options: GetObjectOptions) -> Result<Option<StoredObject>> {
+        let entry = match self.state.borrow().objects.get(key) {
+            Some(entry) => entry.clone(),
+            None => return Ok(None),
+        };
+        let mut info = entry.info;
+
+        if let Some(only_if) = &options.only_if {
+            if !condition_holds(only_if, Some(&info)) {
+                return Ok(Some(StoredObject { info, body: None }));
+            }
+        }
+
+        let body = match options.range {
+            Some(range) => {
+                let (start, end) = range_bounds(&range, info.size)?;
+                info.range = Some(range);
+                entry.body[start as usize..end as usize].to_vec()
+            }
+            None => entry.body,
+        };
+
+        Ok(Some(StoredObject {
+            info,
+            body: Some(StoredBody::Bytes(body)),
+        }))
+    }
+
+    async fn put(
+        &self,
+        key: &str,
+        value: Data,
+        options: PutObjectOptions,
+    ) -> Result<Option<ObjectInfo>> {
+        let body = data_bytes(value).await?;
+
+        let mut state = self.state.borrow_mut();
+        if let Some(only_if) = &options.only_if {
+            let existing = state.objects.get(key).map(|entry| &entry.info);
+            if !condition_holds(only_if, existing) {
+                return Ok(None);
+            }
+        }
+
+        let entry = state.entry(
+            key.into(),
+            body,
+            options.http_metadata.unwrap_or_default(),
+            options.custom_metadata.unwrap_or_default(),
+            options.storage_class.unwrap_or(StorageClass::Standard),
+        );
+        let info = entry.info.clone();
+        state.objects.insert(key.into(), entry);
+
+        Ok(Some(info))
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.state.borrow_mut().objects.remove(key);
+        Ok(())
+    }
+
+    async fn list(&self, options: ListObjectsOptions) -> Result<ObjectList> {
+        let limit = options.limit.unwrap_or(1000).clamp(1, 1000) as usize;
+        let prefix = options.prefix.unwrap_or_default();
+        let state = self.state.borrow();
+
+        let mut list = ObjectList::default();
+        let mut entries = 0;
+        let mut last = None;
+        for (key, entry) in state.objects.range(prefix.clone()..) {
+            if !key.starts_with(&prefix) {
+                break;
+            }
+            if matches!(&options.cursor, Some(cursor) if key <= cursor) {
+                continue;
+            }
+
+            // Keys sharing the part up to the delimiter are listed once, as a delimited prefix.
+            let delimited = options.delimiter.as_ref().and_then(|delimiter| {
+                let rest = &key[prefix.len()..];
+                rest.find(delimiter.as_str())
+                    .map(|index| key[..prefix.len() + index + delimiter.len()].to_string())
+            });
+            if let Some(delimited) = &delimited {
+                let listed = list.delimited_prefixes.last() == Some(delimited)
+                    || matches!(&options.cursor, Some(cursor) if delimited <= cursor);
+                if listed {
+                    continue;
+                }
+            }
+
+            if entries == limit {
+                list.truncated = true;
+                list.cursor = last;
+                break;
+            }
+            entries += 1;
+
+            match delimited {
+                Some(delimited) => {
+                    last = Some(delimited.clone());
+                    list.delimited_prefixes.push(delimited);
+                }
+                None => {
+                    let mut info = entry.info.clone();
+                    if !options.include.contains(&Include::HttpMetadata) {
+                        info.http_metadata = HttpMetadata::default();
+                    }
+                    if !options.include.contains(&Include::CustomMetadata) {
+                        info.custom_metadata = HashMap::new();
+                    }
+                    last = Some(key.clone());
+                    list.objects.push(info);
+                }
+            }
+        }
+
+        Ok(list)
+    }
+
+    async fn create_multipart_upload(
+        &self,
+        key: &str,
+        options: CreateMultipartUploadOptions,
+    ) -> Result<String> {
+        let mut state = self.state.borrow_mut();
+        let upload_id = format!("upload-{}", state.next());
+        state.uploads.insert(
+            upload_id.clone(),
+            Upload {
+                key: key.into(),
+                http_metadata: options.http_metadata.unwrap_or_default(),
+                custom_metadata: options.custom_metadata.unwrap_or_default(),
+                parts: BTreeMap::new(),
+            },
+        );
+
+        Ok(upload_id)
+    }
+
+    async fn upload_part(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_number: u16,
+        value: Data,
+    ) -> Result<PartInfo> {
+        let body = data_bytes(value).await?;
+
+        let mut state = self.state.borrow_mut();
+        let etag = format!("{:032x}", state.next());
+        let upload = upload_mut(&mut state.uploads, key, upload_id)?;
+        upload.parts.insert(part_number, (etag.clone(), body));
+
+        Ok(PartInfo { part_number, etag })
+    }
+
+    async fn complete_multipart_upload(
+        &self,
+        key: &str,
+        upload_id: &str,
+        mut parts: Vec<PartInfo>,
+    ) -> Result<ObjectInfo> {
+        let mut state = self.state.borrow_mut();
+        let upload = upload_mut(&mut state.uploads, key, upload_id)?;
+
+        parts.sort_by_key(|part| part.part_number);
+        let mut body = Vec::new();
+        for part in &parts {
+            match upload.parts.get(&part.part_number) {
+                Some((etag, bytes)) if *etag == part.etag => body.extend_from_slice(bytes),
+                _ => {
+                    return Err(Error::RustError(format!(
+                        "part {} was not uploaded with etag {}",
+                        part.part_number, part.etag
+                    )))
+                }
+            }
+        }
+
+        let upload = state
+            .uploads
+            .remove(upload_id)
+            .expect("the upload was just looked up");
+        let entry = state.entry(
+            upload.key,
+            body,
+            upload.http_metadata,
+            upload.custom_metadata,
+            StorageClass::Standard,
+        );
+        let info = entry.info.clone();
+        state.objects.insert(key.into(), entry);
+
+        Ok(info)
+    }
+
+    async fn abort_multipart_upload(&self, key: &str, upload_id: &str) -> Result<()> {
+        let mut state = self.state.borrow_mut();
+        upload_mut(&mut state.uploads, key, upload_id)?;
+        state.uploads.remove(upload_id);
+        Ok(())
+    }
+}
+
+fn upload_mut<'a>(
+    uploads: &'a mut HashMap<String, Upload>,
+    key: &str,
+    upload_id: &str,
+) -> Result<&'a mut Upload> {
+    uploads
+        .get_mut(upload_id)
+        .filter(|upload| upload.key == key)
+        .ok_or_else(|| Error::RustError(format!("no multipart upload `{upload_id}` for `{key}`")))
+}
+
+async fn data_bytes(data: Data) -> Result<Vec<u8>> {
+    Ok(match data {
+        Data::Stream(stream) => stream.try_concat().await?,
+        Data::Text(text) => text.into_bytes(),
+        Data::Bytes(bytes) => bytes,
+        Data::Empty => Vec::new(),
+    })
+}
+
+/// Whether the condition holds for the object, if any, like R2 evaluates it: a missing object
+/// only fails a condition on its etag matching.
+fn condition_holds(only_if: &Conditional, object: Option<&ObjectInfo>) -> bool {
+    let object = match object {
+        Some(object) => object,
+        None => return only_if.etag_matches.is_none(),
+    };
+
+    let etag_matches = |etag: &String| etag.trim_matches('"') == object.etag;
+    if !only_if.etag_matches.iter().all(etag_matches) {
+        return false;
+    }
+    if only_if.etag_does_not_match.iter().any(etag_matches) {
+        return false;
+    }
+    if let Some(before) = &only_if.uploaded_before {
+        if object.uploaded_millis >= before.as_millis() {
+            return false;
+        }
+    }
+    if let Some(after) = &only_if.uploaded_after {
+        if object.uploaded_millis <= after.as_millis() {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// The start and end (exclusive) of the bytes selected by the range, failing like R2 does if it
+/// starts past the end of the object.
+fn range_bounds(range: &Range, size: u64) -> Result<(u64, u64)> {
+    let (start, length) = match *range {
+        Range::OffsetWithLength { offset, length } => (offset, Some(length)),
+        Range::OffsetWithOptionalLength { offset, length } => (offset, length),
+        Range::OptionalOffsetWithLength { offset, length } => (offset.unwrap_or(0), Some(length)),
+        Range::Suffix { suffix } => (size.saturating_sub(suffix), None),
+    };
+
+    if start > size || (start == size && size > 0) {
+        return Err(Error::RustError(format!(
+            "range starting at {start} is not satisfiable for an object of {size} bytes"
+        )));
+    }
+
+    let end = match length {
+        Some(length) => start.saturating_add(length).min(size),
+        None => size,
+    };
+    Ok((start, end))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn now_millis() -> u64 {
+    crate::Date::now().as_millis()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis() as u64)
+}
+
+// Every operation of the in-memory bucket completes without yielding.
+#[cfg(test)]
+fn run<T>(future: impl std::future::Future<Output = T>) -> T {
+    use futures_util::FutureExt;
+
+    future.now_or_never().expect("operation should not yield")
+}
+
+#[cfg(test)]
+fn text(object: Option<StoredObject>) -> String {
+    let body = object.unwrap().body.unwrap();
+    run(body.text()).unwrap()
+}
+
+#[test]
+fn conditional_get_and_put() {
+    let bucket = MemoryBucket::new();
+    bucket.insert("key", "value");
+    let etag = run(bucket.head("key")).unwrap().unwrap().etag;
+
+    let only_if = |condition: Conditional| GetObjectOptions {
+        only_if: Some(condition),
+        ..Default::default()
+    };
+    let object = run(bucket.get(
+        "key",
+        only_if(Conditional {
+            etag_does_not_match: Some(etag.clone()),
+            ..Default::default()
+        }),
+    ))
+    .unwrap()
+    .unwrap();
+    assert!(object.body.is_none());
+
+    let put = |etag: &str| {
+        run(bucket.put(
+            "key",
+            "new".to_string().into(),
+            PutObjectOptions {
+                only_if: Some(Conditional {
+                    etag_matches: Some(etag.to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        ))
+        .unwrap()
+    };
+    assert!(put(&etag).is_some());
+    assert!(put(&etag).is_none());
+    assert_eq!(
+        text(run(bucket.get("key", Default::default())).unwrap()),
+        "new"
+    );
+}
+
+#[test]
+fn ranged_get() {
+    let bucket = MemoryBucket::new();
+    bucket.insert("key", "0123456789");
+
+    let get = |range| {
+        run(bucket.get(
+            "key",
+            GetObjectOptions {
+                range: Some(range),
+                ..Default::default()
+            },
+        ))
+    };
+    let object = get(Range::OffsetWithLength {
+        offset: 2,
+        length: 3,
+    })
+    .unwrap();
+    assert_eq!(text(object), "234");
+    assert_eq!(text(get(Range::Suffix { suffix: 2 }).unwrap()), "89");
+    assert!(get(Range::OffsetWithOptionalLength {
+        offset: 10,
+        length: None
+    })
+    .is_err());
+}
+
+#[test]
+fn list_with_delimiter_and_cursor() {
+    let bucket = MemoryBucket::new();
+    for key in ["a/1", "a/2", "b", "c/1", "d"] {
+        bucket.insert(key, "");
+    }
+
+    let list = |cursor: Option<String>| {
+        run(bucket.list(ListObjectsOptions {
+            limit: Some(2),
+            cursor,
+            delimiter: Some("/".into()),
+            ..Default::default()
+        }))
+        .unwrap()
+    };
+
+    let page = list(None);
+    assert_eq!(page.delimited_prefixes, vec!["a/"]);
+    assert_eq!(page.objects[0].key, "b");
+    assert!(page.truncated);
+
+    let page = list(page.cursor);
+    assert_eq!(page.delimited_prefixes, vec!["c/"]);
+    assert_eq!(page.objects[0].key, "d");
+    assert!(!page.truncated);
+    assert_eq!(page.cursor, None);
+}
+
+#[test]
+fn list_includes_metadata_on_request() {
+    let bucket = MemoryBucket::new();
+    let mut custom_metadata = HashMap::new();
+    custom_metadata.insert("a".to_string(), "b".to_string());
+    run(bucket.put(
+        "key",
+        Data::Empty,
+        PutObjectOptions {
+            custom_metadata: Some(custom_metadata.clone()),
+            ..Default::default()
+        },
+    ))
+    .unwrap();
+
+    let list = |include| {
+        run(bucket.list(ListObjectsOptions {
+            include,
+            ..Default::default()
+        }))
+        .unwrap()
+    };
+    assert!(list(vec![]).objects[0].custom_metadata.is_empty());
+    assert_eq!(
+        list(vec![Include::CustomMetadata]).objects[0].custom_metadata,
+        custom_metadata
+    );
+}
+
+#[test]
+fn multipart_upload() {
+    let bucket = MemoryBucket::new();
+    let upload_id = run(bucket.create_multipart_upload("key", Default::default())).unwrap();
+
+    let second = run(bucket.upload_part("key", &upload_id, 2, "world".to_string().into())).unwrap();
+    let first = run(bucket.upload_part("key", &upload_id, 1, "hello ".to_string().into())).unwrap();
+    let info =
+        run(bucket.complete_multipart_upload("key", &upload_id, vec![second, first])).unwrap();
+
+    assert_eq!(info.size, 11);
+    assert_eq!(bucket.bytes("key").unwrap(), b"hello world");
+    assert!(run(bucket.abort_multipart_upload("key", &upload_id)).is_err());
+}
@@ -0,0 +1,155 @@
+//! Integration with [`tower`](https://docs.rs/tower) services, allowing a
+//! [`Service`] handling [`http::Request`]s, such as an `axum` router or a service wrapped in
+//! `tower-http` layers, to be used as the fetch entry point of a Worker.
+//!
+//! ```no_run
+//! #[event(fetch)]
+//! pub async fn main(req: Request, env: Env, ctx: Context) -> Result<Response> {
+//!     let service = ServiceBuilder::new()
+//!         .layer(CorsLayer::permissive())
+//!         .service_fn(handle);
+//!     worker::tower::serve(service, req, env, ctx).await
+//! }
+//!
+//! async fn handle(req: http::Request<HttpBody>) -> Result<http::Response<String>> {
+//!     let env = req.extensions().get::<WorkerEnv>().unwrap();
+//!     let greeting = env.var("GREETING")?.to_string();
+//!     Ok(http::Response::new(greeting))
+//! }
+//! ```
+
+use std::{
+    convert::TryFrom,
+    fmt::Display,
+    mem::ManuallyDrop,
+    ops::Deref,
+    thread::{self, ThreadId},
+};
+
+use futures_util::future::poll_fn;
+use tower_service::Service;
+
+use crate::{Context, Env, Error, HttpBody, Request, Response, Result};
+
+/// The [Env] of the fetch event being served, available in the extensions of the requests passed
+/// to the service by [serve].
+#[derive(Clone)]
+pub struct WorkerEnv(ThreadBound<Env>);
+
+/// The [Context] of the fetch event being served, available in the extensions of the requests
+/// passed to the service by [serve].
+#[derive(Clone)]
+pub struct WorkerContext(ThreadBound<Context>);
+
+impl Deref for WorkerEnv {
+    type Target = Env;
+
+    fn deref(&self) -> &Env {
+        &self.0
+    }
+}
+
+impl Deref for WorkerContext {
+    type Target = Context;
+
+    fn deref(&self) -> &Context {
+        &self.0
+    }
+}
+
+/// A value that can only be used on the thread it was created on, which makes it `Send` and
+/// `Sync` as request extensions must be.
+///
+/// Workers run on a single thread, so this only panics if a value is sent to another thread, e.g.
+/// by a service compiled for and tested on a multi-threaded native target.
+struct ThreadBound<T> {
+    value: ManuallyDrop<T>,
+    thread: ThreadId,
+}
+
+// SAFETY: the value is only ever accessed, cloned or dropped on the thread it was created on, and
+// any attempt to do so on another thread panics (or leaks the value when already panicking).
+unsafe impl<T> Send for ThreadBound<T> {}
+unsafe impl<T> Sync for ThreadBound<T> {}
+
+impl<T> ThreadBound<T> {
+    fn new(value: T) -> Self {
+        Self {
+            value: ManuallyDrop::new(value),
+            thread: thread::current().id(),
+        }
+    }
+
+    fn is_on_thread(&self) -> bool {
+        thread::current().id() == self.thread
+    }
+}
+
+impl<T> Deref for ThreadBound<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        assert!(
+            self.is_on_thread(),
+            "worker values can't be used outside of the thread handling the fetch event"
+        );
+        &self.value
+    }
+}
+
+impl<T: Clone> Clone for ThreadBound<T> {
+    fn clone(&self) -> Self {
+        Self::new(T::clone(self))
+    }
+}
+
+impl<T> Drop for ThreadBound<T> {
+    fn drop(&mut self) {
+        if self.is_on_thread() {
+            // SAFETY: the value is never used again.
+            unsafe { ManuallyDrop::drop(&mut self.value) };
+        } else if !thread::panicking() {
+            panic!("worker values can't be dropped outside of the thread handling the fetch event");
+        }
+    }
+}
+
+/// Handles the request of a fetch event with the given service, returning its response.
+///
+/// The request is converted into an [`http::Request`] with a streaming [HttpBody], and the `env`
+/// and `ctx` of the event are inserted into its extensions as [WorkerEnv] and [WorkerContext].
+/// Errors returned by the service are converted into [Error]s.
+pub async fn serve<S, B>(mut service: S, req: Request, env: Env, ctx: Context) -> Result<Response>
+where
+    S: Service<http::Request<HttpBody>, Response = http::Response<B>>,
+    S::Error: Display,
+    B: Into<HttpBody>,
+{
+    let mut req = http::Request::try_from(req)?;
+    req.extensions_mut()
+        .insert(WorkerEnv(ThreadBound::new(env)));
+    req.extensions_mut()
+        .insert(WorkerContext(ThreadBound::new(ctx)));
+
+    let service_error = |e: S::Error| Error::RustError(e.to_string());
+    poll_fn(|cx| service.poll_ready(cx))
+        .await
+        .map_err(service_error)?;
+    let res = service.call(req).await.map_err(service_error)?;
+
+    Response::try_from(res)
+}
+
+#[test]
+fn thread_bound_values_stay_on_their_thread() {
+    let value = ThreadBound::new(String::from("env"));
+    assert_eq!(value.clone().as_str(), "env");
+
+    let shared = std::sync::Arc::new(value);
+    let result = thread::spawn({
+        let shared = shared.clone();
+        move || shared.len()
+    })
+    .join();
+    assert!(result.is_err());
+}